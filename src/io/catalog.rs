@@ -0,0 +1,132 @@
+use crate::model::{Color, ColorInfo};
+use crate::model::difference::nearest;
+use std::path::Path;
+use serde::Deserialize;
+
+/// Marker of catalog files
+pub const CATALOG_FORMAT: &str = "beads-and-threads-catalog";
+pub const CATALOG_VERSION: u64 = 1;
+
+const EXAMPLE: &str = include_str!("../../resources/catalogs/example.json");
+
+/// Bead colors of a manufacturer to pick palette colors from
+#[derive(Debug, Clone, PartialEq)]
+pub struct Catalog {
+    pub name: String,
+    pub colors: Vec<(Color, ColorInfo)>,
+}
+
+/// Catalog file:
+/// `{"format": "beads-and-threads-catalog", "version": 1, "name": "...", "manufacturer": "...",
+/// "colors": [{"color": "#RRGGBB", "code": "...", "name": "...", "finish": "..."}]}`,
+/// the manufacturer of the file is used for the colors without one
+#[derive(Deserialize)]
+struct CatalogFile {
+    format: String,
+    version: u64,
+    name: String,
+    #[serde(default)]
+    manufacturer: String,
+    colors: Vec<Entry>,
+}
+
+#[derive(Deserialize)]
+struct Entry {
+    color: String,
+    #[serde(flatten)]
+    info: ColorInfo,
+}
+
+impl Catalog {
+    /// The catalog shipped with the application
+    pub fn example() -> Self {
+        parse_catalog(EXAMPLE).expect("bundled catalog is valid")
+    }
+    /// Each color with the perceptually nearest catalog color, its info and the CIEDE2000 difference.
+    /// Empty for an empty catalog
+    pub fn snap(&self, colors: &[Color]) -> Vec<(Color, Color, ColorInfo, f64)> {
+        let candidates: Vec<_> = self.colors.iter().map(|(color, _)|*color).collect();
+        colors.iter()
+            .filter_map(|&color|nearest(color, &candidates).map(|(index, difference)|{
+                let (to, info) = self.colors[index].clone();
+                (color, to, info, difference)
+            }))
+            .collect()
+    }
+}
+
+pub fn parse_catalog(text: &str) -> Result<Catalog, String> {
+    let file: CatalogFile = serde_json::from_str(text)
+        .map_err(|e|format!("Broken catalog file: {}", e))?;
+    if file.format != CATALOG_FORMAT {
+        return Err("Not a catalog file".to_string());
+    }
+    if file.version > CATALOG_VERSION {
+        return Err(format!(
+            "The catalog is made for a newer version of the application (catalog format {}, supported {})",
+            file.version, CATALOG_VERSION
+        ));
+    }
+    let manufacturer = file.manufacturer;
+    let colors = file.colors.into_iter()
+        .enumerate()
+        .map(|(i, Entry {color, mut info})| {
+            let color = color.parse::<Color>()
+                .map_err(|_|format!("Catalog color {}: wrong color \"{}\"", i + 1, color))?;
+            if info.manufacturer.is_empty() {
+                info.manufacturer = manufacturer.clone();
+            }
+            Ok((color, info))
+        })
+        .collect::<Result<_, String>>()?;
+    Ok(Catalog {name: file.name, colors})
+}
+
+pub fn load_catalog<T: AsRef<Path>>(path: T) -> Result<Catalog, String> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path)
+        .map_err(|e|format!("Unable to read {}: {}", path.display(), e))?;
+    parse_catalog(&text)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn catalog() {
+        let example = Catalog::example();
+        assert!(!example.colors.is_empty());
+        assert!(example.colors.iter().all(|(_, info)|!info.manufacturer.is_empty()));
+
+        let catalog = parse_catalog(r##"{"format": "beads-and-threads-catalog", "version": 1, "name": "Test",
+            "manufacturer": "Maker", "colors": [
+                {"color": "#000000", "code": "1", "name": "Black", "finish": "matte"},
+                {"color": "fff", "manufacturer": "Other"}
+            ]}"##).unwrap();
+        assert_eq!(catalog.name, "Test");
+        assert_eq!(catalog.colors[0].1.label(), "Maker 1 Black (matte)");
+        assert_eq!(catalog.colors[1], (Color {r: 255, g: 255, b: 255}, ColorInfo {
+            manufacturer: "Other".to_string(),
+            ..Default::default()
+        }));
+
+        assert_eq!(
+            parse_catalog(r#"{"format": "other", "version": 1, "name": "", "colors": []}"#).unwrap_err(),
+            "Not a catalog file"
+        );
+        assert_eq!(
+            parse_catalog(r#"{"format": "beads-and-threads-catalog", "version": 1, "name": "", "colors": [{"color": "x"}]}"#)
+                .unwrap_err(),
+            "Catalog color 1: wrong color \"x\""
+        );
+
+        let snapped = catalog.snap(&[Color {r: 20, g: 10, b: 10}, Color {r: 255, g: 255, b: 255}]);
+        assert_eq!(snapped.len(), 2);
+        assert_eq!((snapped[0].1, snapped[0].2.name.as_str()), (Color {r: 0, g: 0, b: 0}, "Black"));
+        assert!(snapped[0].3 > 1.0);
+        assert_eq!(snapped[1].1, Color {r: 255, g: 255, b: 255});
+        assert!(snapped[1].3 < 1e-6);
+        assert!(Catalog {name: String::new(), colors: Vec::new()}.snap(&[Color::default()]).is_empty());
+    }
+}
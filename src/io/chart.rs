@@ -0,0 +1,198 @@
+use crate::model::{Grid, ColorBead, Color, Bead, Schema};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+/// Limits of the bead side in pixels
+pub const MIN_BEAD_SIZE: u32 = 4;
+pub const MAX_BEAD_SIZE: u32 = 64;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ChartOptions {
+    /// bead side in pixels
+    pub bead_size: u32,
+    /// red border around the beads starting the line
+    pub markers: bool,
+    pub row_numbers: bool,
+}
+
+impl Default for ChartOptions {
+    fn default() -> Self {
+        Self {
+            bead_size: 16,
+            markers: true,
+            row_numbers: true,
+        }
+    }
+}
+
+const BORDER: Color = Color {r: 0, g: 0, b: 0};
+const MARKER: Color = Color {r: 230, g: 0, b: 0};
+const TEXT: Color = Color {r: 0x40, g: 0x40, b: 0x40};
+
+/// 3×5 digits, a row of bits per line
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b010, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+/// RGB picture drawn in memory
+pub struct Canvas {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(width: u32, height: u32) -> Result<Self, String> {
+        let Color {r, g, b} = Color::default();
+        let size = (width as usize).checked_mul(height as usize)
+            .and_then(|pixels|pixels.checked_mul(3))
+            .ok_or_else(||format!("The chart of {}×{} pixels is too large", width, height))?;
+        Ok(Self {
+            width,
+            height,
+            data: [r, g, b].iter().cycle().take(size).copied().collect(),
+        })
+    }
+    fn rect(&mut self, x: u32, y: u32, width: u32, height: u32, Color {r, g, b}: Color) {
+        for row in y..(y + height).min(self.height) {
+            for column in x..(x + width).min(self.width) {
+                let at = (row as usize * self.width as usize + column as usize) * 3;
+                self.data[at..at + 3].copy_from_slice(&[r, g, b]);
+            }
+        }
+    }
+    /// Number ending at `right`, digits are `scale` pixels per dot
+    fn number(&mut self, number: usize, right: u32, y: u32, scale: u32) {
+        let text = number.to_string();
+        let mut x = right - text.len() as u32 * 4 * scale;
+        for digit in text.bytes().map(|c|(c - b'0') as usize) {
+            for (line, bits) in DIGITS[digit].iter().enumerate() {
+                for dot in 0..3 {
+                    if bits & (0b100 >> dot) != 0 {
+                        self.rect(x + dot * scale, y + line as u32 * scale, scale, scale, TEXT);
+                    }
+                }
+            }
+            x += 4 * scale;
+        }
+    }
+}
+
+/// Draws the grid as the grid plate shows it: offset rows, the seam rotated by `rotation` columns
+pub fn render(grid: &Grid<ColorBead>, schema: Schema, rotation: isize, options: ChartOptions) -> Result<Canvas, String> {
+    let full = options.bead_size.clamp(MIN_BEAD_SIZE, MAX_BEAD_SIZE);
+    let half = full / 2;
+    let portions = match schema {
+        Schema::FirstOffset => [full, half, full],
+        Schema::SecondOffset => [half, full, half],
+        Schema::Straight => [half, half, half],
+    };
+    let width = grid.width();
+    let rotation = rotation.rem_euclid(width as isize) as usize;
+    let height = grid.size().height();
+    let scale = (full / 6).max(1);
+    let margin = if options.row_numbers {
+        (height.to_string().len() as u32 * 4 + 2) * scale
+    } else {
+        0
+    };
+    let too_large = ||format!("The chart of {}×{} beads is too large", width, height);
+    let canvas_width = (width as u32).checked_mul(full)
+        .and_then(|beads|beads.checked_add(margin + portions[0] + portions[1]))
+        .ok_or_else(too_large)?;
+    let canvas_height = (height as u32).checked_mul(full).ok_or_else(too_large)?;
+    let mut canvas = Canvas::new(canvas_width, canvas_height)?;
+    for (index, row) in grid.as_full_table_iter().enumerate() {
+        let y = index as u32 * full;
+        if options.row_numbers {
+            canvas.number(index + 1, margin - 2 * scale, y + full.saturating_sub(5 * scale) / 2, scale);
+        }
+        let left = margin + portions[index % 2];
+        let beads = row.cycle().skip(rotation).take(width);
+        for (position, (Bead {color, ..}, first)) in beads.enumerate() {
+            let x = left + position as u32 * full;
+            let border = if options.markers && *first { MARKER } else { BORDER };
+            canvas.rect(x, y, full, full, border);
+            canvas.rect(x + 1, y + 1, full - 2, full - 2, *color);
+        }
+    }
+    Ok(canvas)
+}
+
+pub fn save_chart<T: AsRef<Path>>(path: T, grid: &Grid<ColorBead>, schema: Schema, rotation: isize, options: ChartOptions) -> Result<(), String> {
+    if !(MIN_BEAD_SIZE..=MAX_BEAD_SIZE).contains(&options.bead_size) {
+        return Err(format!("Bead size must be from {} to {} pixels", MIN_BEAD_SIZE, MAX_BEAD_SIZE));
+    }
+    let canvas = render(grid, schema, rotation, options)?;
+    let file = File::create(path).map_err(|e|e.to_string())?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), canvas.width, canvas.height);
+    encoder.set_color(png::ColorType::RGB);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()
+        .and_then(|mut writer|writer.write_image_data(&canvas.data))
+        .map_err(|e|e.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::model::Model;
+
+    fn pixel(canvas: &Canvas, x: u32, y: u32) -> Color {
+        let at = (y as usize * canvas.width as usize + x as usize) * 3;
+        Color {r: canvas.data[at], g: canvas.data[at + 1], b: canvas.data[at + 2]}
+    }
+
+    #[test]
+    fn chart() {
+        let black = Color {r: 0, g: 0, b: 0};
+        let mut model = Model::default();
+        model.activate_color(black);
+        model.set(0, 0).unwrap();
+        let options = ChartOptions {bead_size: 10, markers: false, row_numbers: false};
+        let grid = model.grid();
+        let (width, height) = (grid.width() as u32, grid.size().height() as u32);
+
+        let canvas = render(grid, Schema::FirstOffset, 0, options).unwrap();
+        assert_eq!((canvas.width, canvas.height), (width * 10 + 15, height * 10));
+        // the first row is shifted by a whole bead, the second one by a half
+        assert_eq!(pixel(&canvas, 5, 5), Color::default());
+        assert_eq!(pixel(&canvas, 15, 5), black);
+        assert_eq!(pixel(&canvas, 4, 15), Color::default());
+        assert_eq!(pixel(&canvas, 5, 15), BORDER);
+
+        let canvas = render(grid, Schema::Straight, 1, options).unwrap();
+        assert_eq!(pixel(&canvas, 5 + (width - 1) * 10 + 5, 5), black);
+
+        let numbered = render(grid, Schema::Straight, 0, ChartOptions {row_numbers: true, ..options}).unwrap();
+        assert!(numbered.width > canvas.width);
+        let smallest = ChartOptions {bead_size: MIN_BEAD_SIZE, row_numbers: true, ..options};
+        assert!(render(grid, Schema::FirstOffset, 0, smallest).is_ok());
+
+        let huge = render(grid, Schema::Straight, 0, ChartOptions {bead_size: u32::MAX, ..options}).unwrap();
+        assert_eq!(huge.height, height * MAX_BEAD_SIZE);
+        assert!(Canvas::new(u32::MAX, u32::MAX).is_err());
+    }
+
+    #[test]
+    fn png_file() {
+        let model = Model::<Color>::default();
+        let path = std::env::temp_dir().join("beads_chart_test.png");
+        save_chart(&path, model.grid(), model.schema(), 0, ChartOptions::default()).unwrap();
+        let data = std::fs::read(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(data.unwrap().starts_with(b"\x89PNG"));
+        let options = ChartOptions {bead_size: MAX_BEAD_SIZE + 1, ..Default::default()};
+        assert!(save_chart(&path, model.grid(), model.schema(), 0, options).is_err());
+    }
+}
@@ -0,0 +1,235 @@
+use crate::model::{Grid, ColorBead, Color, Bead, BeadsLine, Palette};
+use std::fs::File;
+use std::io::{Write, BufWriter};
+use std::fmt;
+use std::num::NonZeroUsize;
+use std::path::Path;
+
+/// Problems shown to the user at most, the rest are only counted
+const SHOWN_ISSUES: usize = 10;
+
+/// A problem with a single place of the CSV grid, rows and columns count from 1
+#[derive(Debug, Clone, PartialEq)]
+pub enum CsvIssue {
+    Cell { row: usize, column: usize, text: String },
+    RowLength { row: usize, expected: usize, found: usize },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CsvError {
+    Io(String),
+    Empty,
+    Invalid(Vec<CsvIssue>),
+}
+
+impl fmt::Display for CsvIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvIssue::Cell {row, column, text} => write!(f, "row {}, column {}: unknown color \"{}\"", row, column, text),
+            CsvIssue::RowLength {row, expected, found} => write!(f, "row {}: {} cells instead of {}", row, found, expected),
+        }
+    }
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvError::Io(e) => write!(f, "Unable to read CSV: {}", e),
+            CsvError::Empty => write!(f, "CSV has no cells"),
+            CsvError::Invalid(issues) => {
+                write!(f, "Invalid CSV grid:")?;
+                for issue in issues.iter().take(SHOWN_ISSUES) {
+                    write!(f, "\n{}", issue)?;
+                }
+                if issues.len() > SHOWN_ISSUES {
+                    write!(f, "\n...and {} more", issues.len() - SHOWN_ISSUES)?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+/// Reads a CSV of colors, blank cells get the background color
+pub fn load_grid<T: AsRef<Path>>(path: T, background: Color) -> Result<Grid<ColorBead>, CsvError> {
+    let text = std::fs::read_to_string(path).map_err(|e|CsvError::Io(e.to_string()))?;
+    parse_grid(&text, background)
+}
+
+fn parse_grid(text: &str, background: Color) -> Result<Grid<ColorBead>, CsvError> {
+    let text = text.trim_start_matches('\u{feff}');
+    // spreadsheets of some locales separate cells by semicolons, commas are decimal separators there
+    let delimiter = if text.contains(';') { ';' } else { ',' };
+    let rows: Vec<_> = text.lines()
+        .enumerate()
+        .filter(|(_, line)|!line.trim().is_empty())
+        .map(|(i, line)|(i + 1, split(line, delimiter)))
+        .collect();
+    let width = rows.first().map_or(0, |(_, cells)|cells.len());
+    let width = NonZeroUsize::new(width).ok_or(CsvError::Empty)?;
+
+    let mut issues = Vec::new();
+    let mut data = Vec::with_capacity(rows.len() * width.get());
+    for (row, cells) in rows {
+        if cells.len() != width.get() {
+            issues.push(CsvIssue::RowLength {row, expected: width.get(), found: cells.len()});
+            continue;
+        }
+        for (i, cell) in cells.into_iter().enumerate() {
+            if cell.is_empty() {
+                data.push((background, false));
+            } else {
+                match cell.parse() {
+                    Ok(color) => data.push((color, false)),
+                    Err(_) => issues.push(CsvIssue::Cell {row, column: i + 1, text: cell.to_string()}),
+                }
+            }
+        }
+    }
+    if !issues.is_empty() {
+        return Err(CsvError::Invalid(issues));
+    }
+    let grid = Grid::frow_raw(width, data)
+        .map_err(|e|CsvError::Io(e.to_string()))?
+        .map(|item|Bead {
+            color: *item,
+            filled: false,
+        });
+    Ok(grid)
+}
+
+/// Splits the line into trimmed cells, delimiters inside quotes and parentheses are kept
+fn split(line: &str, delimiter: char) -> Vec<&str> {
+    let mut cells = Vec::new();
+    let mut quoted = false;
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '(' if !quoted => depth += 1,
+            ')' if !quoted => depth = depth.saturating_sub(1),
+            c if c == delimiter && !quoted && depth == 0 => {
+                cells.push(unquote(&line[start..i]));
+                start = i + c.len_utf8();
+            },
+            _ => {},
+        }
+    }
+    cells.push(unquote(&line[start..]));
+    cells
+}
+
+fn unquote(cell: &str) -> &str {
+    let cell = cell.trim();
+    if cell.len() >= 2 && cell.starts_with('"') && cell.ends_with('"') {
+        cell[1..cell.len() - 1].trim()
+    } else {
+        cell
+    }
+}
+
+pub fn save_grid<T: AsRef<Path>>(path: T, grid: &Grid<ColorBead>) -> Result<(), String> {
+    let file = File::create(path).map_err(|e|e.to_string())?;
+    write_grid(BufWriter::new(file), grid).map_err(|e|e.to_string())
+}
+
+fn write_grid<W: Write>(mut writer: W, grid: &Grid<ColorBead>) -> std::io::Result<()> {
+    for row in grid.as_table_iter() {
+        let row: Vec<_> = row.map(|Bead {color, ..}|color.to_string()).collect();
+        writeln!(writer, "{}", row.join(","))?;
+    }
+    writer.flush()
+}
+
+pub fn save_line<T: AsRef<Path>>(path: T, line: &BeadsLine<ColorBead>, palette: &Palette<Color>) -> Result<(), String> {
+    let file = File::create(path).map_err(|e|e.to_string())?;
+    write_line(BufWriter::new(file), line, palette).map_err(|e|e.to_string())
+}
+
+/// Quotes the cell with delimiters or quotes inside
+fn quoted(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+fn write_line<W: Write>(mut writer: W, line: &BeadsLine<ColorBead>, palette: &Palette<Color>) -> std::io::Result<()> {
+    writeln!(writer, "number,color,count,filled,name")?;
+    for (i, (Bead {color, filled}, count)) in line.line().iter().enumerate() {
+        let name = palette.info(color).map(|info|info.label()).unwrap_or_default();
+        writeln!(writer, "{},{},{},{},{}", i + 1, color, count, filled, quoted(&name))?;
+    }
+    writer.flush()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::model::{Model, ColorInfo};
+
+    fn colors(grid: &Grid<ColorBead>) -> Vec<Vec<Color>> {
+        grid.as_table_iter().map(|row|row.map(|bead|bead.color).collect()).collect()
+    }
+
+    #[test]
+    fn grid_csv_round_trip() {
+        let mut model = Model::default();
+        model.activate_color(Color {r: 0x12, g: 0xab, b: 0xff});
+        model.set(0, 0).unwrap();
+        model.set(3, 5).unwrap();
+        let path = std::env::temp_dir().join("beads_grid_csv_round_trip.csv");
+        save_grid(&path, model.grid()).unwrap();
+        let loaded = load_grid(&path, Color::default());
+        std::fs::remove_file(&path).unwrap();
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded.size().width(), model.size().width());
+        assert_eq!(loaded.size().height(), model.size().height());
+        assert_eq!(colors(&loaded), colors(model.grid()));
+    }
+
+    #[test]
+    fn line_csv() {
+        let mut model = Model::default();
+        model.activate_color(Color {r: 0, g: 0, b: 0});
+        model.set(0, 0).unwrap();
+        model.toggle_filled(0).unwrap();
+        model.set_color_info(Color {r: 0, g: 0, b: 0}, ColorInfo {name: "Black, \"jet\"".to_string(), ..Default::default()});
+        let mut out = Vec::new();
+        write_line(&mut out, model.line(), model.palette()).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let mut lines = out.lines();
+        assert_eq!(lines.next(), Some("number,color,count,filled,name"));
+        let (Bead {color, ..}, count) = &model.line().line()[0];
+        assert_eq!(lines.next(), Some(format!("1,{},{},true,", color, count).as_str()));
+        assert!(out.contains(",#000000,1,false,\"Black, \"\"jet\"\"\""));
+        assert_eq!(lines.count() + 1, model.line().line().len());
+    }
+
+    #[test]
+    fn color_variants() {
+        let background = Color {r: 1, g: 1, b: 1};
+        let text = "\u{feff}#FF0000; ff0000 ;\"#f00\"\n rgb(255, 0, 0);;RGB(0,0,255)\n\n";
+        let grid = parse_grid(text, background).unwrap();
+        let red = Color {r: 255, g: 0, b: 0};
+        let blue = Color {r: 0, g: 0, b: 255};
+        assert_eq!(colors(&grid), vec![vec![red, red, red], vec![red, background, blue]]);
+
+        let grid = parse_grid("rgb(0,0,255),,#00f", background).unwrap();
+        assert_eq!(colors(&grid), vec![vec![blue, background, blue]]);
+    }
+
+    #[test]
+    fn csv_errors() {
+        assert_eq!(parse_grid("\n\n", Color::default()).unwrap_err(), CsvError::Empty);
+        let error = parse_grid("#000,#fff\n#000,red\n#000\n#000,#12345", Color::default()).unwrap_err();
+        assert_eq!(error, CsvError::Invalid(vec![
+            CsvIssue::Cell {row: 2, column: 2, text: "red".to_string()},
+            CsvIssue::RowLength {row: 3, expected: 2, found: 1},
+            CsvIssue::Cell {row: 4, column: 2, text: "#12345".to_string()},
+        ]));
+        assert_eq!(error.to_string().lines().nth(1), Some("row 2, column 2: unknown color \"red\""));
+    }
+}
@@ -0,0 +1,199 @@
+use crate::model::{ColorBead, Color, ColorInfo, Model, Palette, ViewState};
+use crate::model::beads::BeadsLine;
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+
+/// Marker of pattern files, written along with the version
+pub const FORMAT: &str = "beads-and-threads";
+pub const DOCUMENT_VERSION: u64 = 1;
+
+/// Everything saved to a pattern file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Document {
+    pub palette: Vec<Color>,
+    pub active: Color,
+    pub line: BeadsLine<ColorBead>,
+    pub view: ViewState,
+    /// symbols of the colors, the files without them get generated ones
+    #[serde(default)]
+    pub symbols: Vec<(Color, String)>,
+    /// names and catalog numbers of the palette colors
+    #[serde(default)]
+    pub info: Vec<(Color, ColorInfo)>,
+}
+
+#[derive(Serialize)]
+struct Envelope<'a> {
+    format: &'a str,
+    version: u64,
+    #[serde(flatten)]
+    document: &'a Document,
+}
+
+impl Document {
+    pub fn new(model: &Model<Color>, view: ViewState) -> Self {
+        let mut symbols: Vec<_> = model.symbols().assigned().iter()
+            .map(|(color, symbol)|(*color, symbol.clone()))
+            .collect();
+        symbols.sort_unstable_by_key(|(color, _)|color.to_string());
+        Self {
+            palette: model.palette().colors().to_vec(),
+            info: model.palette().colors().iter()
+                .filter_map(|color|model.palette().info(color).map(|info|(*color, info.clone())))
+                .collect(),
+            active: *model.palette().activated(),
+            line: model.line().clone(),
+            view,
+            symbols,
+        }
+    }
+    pub fn into_model(self) -> (Model<Color>, ViewState) {
+        let mut model = Model::from(self.line);
+        // colors of the line missing in the saved palette go to its end
+        let mut palette = Palette::from(self.palette);
+        model.palette().colors().iter().for_each(|color|palette.add_color(*color));
+        palette.activate(self.active);
+        self.info.into_iter().for_each(|(color, info)|palette.set_info(color, info));
+        model.set_palette(palette);
+        model.set_symbols(self.symbols.into_iter().collect());
+        (model, self.view)
+    }
+}
+
+impl From<Model<Color>> for Document {
+    fn from(model: Model<Color>) -> Self {
+        Document::new(&model, ViewState::default())
+    }
+}
+
+pub fn save(path: &PathBuf, document: &Document) -> Result<(), String> {
+    let mut file = File::create(path)
+        .map_err(|e|e.to_string())?;
+    let envelope = Envelope {format: FORMAT, version: DOCUMENT_VERSION, document};
+    let serialized = serde_json::to_string(&envelope)
+        .map_err(|e|e.to_string())?;
+    file.write_all(serialized.as_bytes())
+        .map_err(|e|e.to_string())
+}
+
+/// Reads a pattern file, a CSV grid or a bead line saved by older versions.
+/// Blank cells of a CSV grid get the background color
+pub fn load(path: &PathBuf, background: Color) -> Result<Document, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e|format!("Unable to read {}: {}", path.display(), e))?;
+    if text.trim_start().starts_with('{') {
+        parse(&text)
+    } else {
+        super::load_grid(path, background)
+            .map(|grid|Model::from(grid).into())
+            .map_err(|e|e.to_string())
+    }
+}
+
+fn parse(text: &str) -> Result<Document, String> {
+    let value: Value = serde_json::from_str(text)
+        .map_err(|e|format!("Broken pattern file: {}", e))?;
+    let format = value.get("format").map(|format|format.as_str().unwrap_or_default());
+    let version = value.get("version").and_then(Value::as_u64);
+    match (format, version) {
+        (Some(FORMAT), Some(version)) => migrate(value, version),
+        (Some(FORMAT), None) => Err("Broken pattern file: no version".to_string()),
+        (Some(format), _) => Err(format!("Unknown file format \"{}\"", format)),
+        (None, None) if value.get("line").is_some() => migrate(value, 0),
+        (None, _) => Err("Not a pattern file".to_string()),
+    }
+}
+
+/// Brings the document of the given version to the current one
+fn migrate(value: Value, version: u64) -> Result<Document, String> {
+    let broken = |e: serde_json::Error|format!("Broken pattern file of version {}: {}", version, e);
+    match version {
+        0 => {
+            let line: BeadsLine<ColorBead> = serde_json::from_value(value).map_err(broken)?;
+            Ok(Model::from(line).into())
+        },
+        DOCUMENT_VERSION => serde_json::from_value(value).map_err(broken),
+        version if version > DOCUMENT_VERSION => Err(format!(
+            "The pattern is saved by a newer version of the application (file format {}, supported {})",
+            version, DOCUMENT_VERSION
+        )),
+        version => Err(format!("Unknown pattern file version {}", version)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn document_round_trip() {
+        let unused = Color {r: 1, g: 2, b: 3};
+        let active = Color {r: 4, g: 5, b: 6};
+        let mut model = Model::default();
+        model.insert_color(0, unused);
+        model.activate_color(active);
+        model.set(0, 0).unwrap();
+        model.toggle_filled(0).unwrap();
+        model.set_symbol(active, "*".to_string()).unwrap();
+        let info = ColorInfo {name: "Unused".to_string(), code: "12".to_string(), ..Default::default()};
+        model.set_color_info(unused, info.clone());
+        let view = ViewState {rotation: 3, zoom: 9};
+
+        let document = Document::new(&model, view);
+        let envelope = Envelope {format: FORMAT, version: DOCUMENT_VERSION, document: &document};
+        let json = serde_json::to_string(&envelope).unwrap();
+        let (loaded, loaded_view) = parse(&json).unwrap().into_model();
+        assert_eq!(loaded_view, view);
+        assert_eq!(loaded.palette().activated(), &active);
+        assert!(loaded.palette().contains(&unused));
+        assert_eq!(loaded.palette().info(&unused), Some(&info));
+        assert_eq!(loaded.palette().colors(), model.palette().colors());
+        assert_eq!(loaded.line().line(), model.line().line());
+        assert!(loaded.line().line()[0].0.filled);
+        assert_eq!(loaded.symbols().get(&active), "*");
+        assert_eq!(loaded.symbols().assigned(), model.symbols().assigned());
+    }
+
+    #[test]
+    fn migration() {
+        let mut model = Model::default();
+        model.activate_color(Color {r: 4, g: 5, b: 6});
+        model.set(1, 1).unwrap();
+        let line = serde_json::to_string(model.line()).unwrap();
+        let document = parse(&line).unwrap();
+        assert_eq!(document.line.line(), model.line().line());
+        assert_eq!(document.view, ViewState::default());
+
+        // only the bare line comes without the format marker
+        let unmarked = serde_json::to_value(&document).unwrap();
+        let mut unmarked = unmarked.as_object().unwrap().clone();
+        unmarked.insert("version".to_string(), 1.into());
+        assert_eq!(parse(&Value::Object(unmarked).to_string()).unwrap_err(), "Not a pattern file");
+    }
+
+    #[test]
+    fn csv_background() {
+        let background = Color {r: 10, g: 20, b: 30};
+        let path = std::env::temp_dir().join("beads_document_csv_background.csv");
+        std::fs::write(&path, "#000000,\n,#000000\n").unwrap();
+        let document = load(&path, background);
+        std::fs::remove_file(&path).unwrap();
+        let (model, _) = document.unwrap().into_model();
+        assert_eq!(model.grid().get(0, 1).unwrap().color, background);
+        assert_eq!(model.grid().get(1, 0).unwrap().color, background);
+        assert!(model.palette().contains(&background));
+    }
+
+    #[test]
+    fn errors() {
+        assert!(parse("{\"line\": [").unwrap_err().starts_with("Broken pattern file"));
+        assert_eq!(parse("{\"name\": 1}").unwrap_err(), "Not a pattern file");
+        assert_eq!(parse("{\"format\": \"other\", \"version\": 1}").unwrap_err(), "Unknown file format \"other\"");
+        assert!(parse("{\"format\": \"beads-and-threads\", \"version\": 2}").unwrap_err().contains("newer version"));
+        assert!(parse("{\"format\": \"beads-and-threads\", \"version\": 1}").unwrap_err()
+            .starts_with("Broken pattern file of version 1"));
+    }
+}
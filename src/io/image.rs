@@ -0,0 +1,284 @@
+use crate::model::{Grid, ColorBead, Color, Bead, Schema, Size};
+use crate::model::quantize::{median_cut, nearest};
+use std::path::Path;
+
+/// How the colors of the picture are reduced
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Quantization {
+    /// median cut to the given count of colors
+    Colors(usize),
+    /// the closest colors of the current palette
+    Palette,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct ImageOptions {
+    pub size: Size,
+    /// rows are taken from the picture proportions and the bead shape, `size.height` is ignored
+    pub keep_aspect: bool,
+    pub quantization: Quantization,
+}
+
+/// Decoded picture, transparent pixels are blended with the background color
+#[derive(Debug)]
+pub struct Picture {
+    width: usize,
+    height: usize,
+    pixels: Vec<Color>,
+}
+
+fn blend(r: u8, g: u8, b: u8, alpha: u8) -> Color {
+    let background = Color::default();
+    let mix = |value: u8, back: u8| ((value as u32 * alpha as u32 + back as u32 * (255 - alpha as u32)) / 255) as u8;
+    Color {
+        r: mix(r, background.r),
+        g: mix(g, background.g),
+        b: mix(b, background.b),
+    }
+}
+
+impl Picture {
+    pub fn load<T: AsRef<Path>>(path: T) -> Result<Self, String> {
+        let data = std::fs::read(path).map_err(|e|e.to_string())?;
+        if data.starts_with(b"\x89PNG") {
+            Self::from_png(&data)
+        } else if data.starts_with(b"BM") {
+            Self::from_bmp(&data)
+        } else {
+            Err("Only PNG and BMP pictures are supported".to_string())
+        }
+    }
+
+    fn from_png(data: &[u8]) -> Result<Self, String> {
+        // default transformations expand palettes and low bit depths, and strip 16 bits to 8
+        let (info, mut reader) = png::Decoder::new(data).read_info()
+            .map_err(|e|format!("Broken PNG: {}", e))?;
+        let mut buffer = vec![0; info.buffer_size()];
+        reader.next_frame(&mut buffer).map_err(|e|format!("Broken PNG: {}", e))?;
+        let (width, height) = (info.width as usize, info.height as usize);
+        let channels = match info.color_type {
+            png::ColorType::Grayscale => 1,
+            png::ColorType::GrayscaleAlpha => 2,
+            png::ColorType::RGB => 3,
+            png::ColorType::RGBA => 4,
+            png::ColorType::Indexed => return Err("Unsupported PNG color type".to_string()),
+        };
+        let pixels = buffer.chunks(info.line_size)
+            .take(height)
+            .flat_map(|line|line.chunks(channels).take(width))
+            .map(|pixel| match *pixel {
+                [v] => blend(v, v, v, 255),
+                [v, a] => blend(v, v, v, a),
+                [r, g, b] => blend(r, g, b, 255),
+                [r, g, b, a] => blend(r, g, b, a),
+                _ => Color::default(),
+            })
+            .collect();
+        Ok(Self {width, height, pixels})
+    }
+
+    /// Uncompressed BMP of 8, 24 or 32 bits per pixel
+    fn from_bmp(data: &[u8]) -> Result<Self, String> {
+        let broken = || "Broken BMP".to_string();
+        let u16_at = |at: usize| data.get(at..at + 2)
+            .map(|bytes|u16::from_le_bytes([bytes[0], bytes[1]]))
+            .ok_or_else(broken);
+        let u32_at = |at: usize| data.get(at..at + 4)
+            .map(|bytes|u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .ok_or_else(broken);
+        let offset = u32_at(10)? as usize;
+        let header_size = u32_at(14)? as usize;
+        let width = u32_at(18)? as i32;
+        let height = u32_at(22)? as i32;
+        let bits = u16_at(28)?;
+        let compression = u32_at(30)?;
+        // bit fields of 32 bits pictures are expected to be the usual BGRA
+        if !(compression == 0 || (compression == 3 && bits == 32)) || ![8, 24, 32].contains(&bits) {
+            return Err(format!("Unsupported BMP: {} bits per pixel, compression {}", bits, compression));
+        }
+        if width <= 0 || height == 0 {
+            return Err(broken());
+        }
+        let (width, bottom_up) = (width as usize, height > 0);
+        let height = height.unsigned_abs() as usize;
+        let palette = if bits == 8 {
+            let colors = match u32_at(46)? { 0 => 256, count => count as usize };
+            let start = 14 + header_size;
+            data.get(start..start + colors * 4)
+                .ok_or_else(broken)?
+                .chunks(4)
+                .map(|bgra|blend(bgra[2], bgra[1], bgra[0], 255))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let bytes = bits as usize / 8;
+        let stride = (width * bytes).div_ceil(4) * 4;
+        // a broken header may claim more pixels than can be allocated, the file has to hold them all
+        let end = stride.checked_mul(height - 1)
+            .and_then(|rows|rows.checked_add(width * bytes))
+            .and_then(|size|size.checked_add(offset));
+        if end.is_none_or(|end|end > data.len()) {
+            return Err(broken());
+        }
+        let mut pixels = Vec::with_capacity(width * height);
+        for row in 0..height {
+            let row = if bottom_up { height - 1 - row } else { row };
+            let start = offset + row * stride;
+            let line = data.get(start..start + width * bytes).ok_or_else(broken)?;
+            for pixel in line.chunks(bytes) {
+                pixels.push(match *pixel {
+                    [index] => *palette.get(index as usize).ok_or_else(broken)?,
+                    [b, g, r] => blend(r, g, b, 255),
+                    [b, g, r, _] => blend(r, g, b, 255),
+                    _ => return Err(broken()),
+                });
+            }
+        }
+        Ok(Self {width, height, pixels})
+    }
+
+    /// Averages the pixels covered by each cell of the table
+    fn scale(&self, width: usize, height: usize) -> Vec<Color> {
+        let range = |cell: usize, cells: usize, pixels: usize| {
+            let start = cell * pixels / cells;
+            let end = ((cell + 1) * pixels).div_ceil(cells);
+            start..end.max(start + 1).min(pixels)
+        };
+        let mut result = Vec::with_capacity(width * height);
+        for row in 0..height {
+            let rows = range(row, height, self.height);
+            for column in 0..width {
+                let columns = range(column, width, self.width);
+                let mut sum = [0usize; 3];
+                let mut count = 0;
+                for y in rows.clone() {
+                    for &Color {r, g, b} in &self.pixels[y * self.width + columns.start..y * self.width + columns.end] {
+                        sum[0] += r as usize;
+                        sum[1] += g as usize;
+                        sum[2] += b as usize;
+                        count += 1;
+                    }
+                }
+                let count = count.max(1);
+                result.push(Color {
+                    r: (sum[0] / count) as u8,
+                    g: (sum[1] / count) as u8,
+                    b: (sum[2] / count) as u8,
+                });
+            }
+        }
+        result
+    }
+
+    /// Rows keeping the picture proportions for the given count of bead columns
+    fn rows(&self, width: usize, schema: Schema) -> usize {
+        let rows = width as f32 * self.height as f32 / self.width as f32 / schema.row_height();
+        (rows.round() as usize).max(1)
+    }
+
+    pub fn to_grid(&self, options: ImageOptions, schema: Schema, palette: &[Color]) -> Grid<ColorBead> {
+        let width = options.size.width;
+        let height = if options.keep_aspect {
+            self.rows(width.get(), schema)
+        } else {
+            options.size.height()
+        };
+        let pixels = self.scale(width.get(), height);
+        let palette = match options.quantization {
+            Quantization::Colors(count) => median_cut(&pixels, count),
+            Quantization::Palette => palette.to_vec(),
+        };
+        let data = pixels.into_iter()
+            .map(|color|(nearest(color, &palette), false))
+            .collect();
+        Grid::frow_raw(width, data)
+            .expect("scaled picture has rows of the grid width")
+            .map(|color|Bead {color: *color, filled: false})
+    }
+}
+
+pub fn load_image<T: AsRef<Path>>(path: T, options: ImageOptions, schema: Schema, palette: &[Color]) -> Result<Grid<ColorBead>, String> {
+    if let Quantization::Colors(0) = options.quantization {
+        return Err("At least one color is needed".to_string());
+    }
+    Ok(Picture::load(path)?.to_grid(options, schema, palette))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::num::NonZeroUsize;
+
+    fn colors(grid: &Grid<ColorBead>) -> Vec<Vec<Color>> {
+        grid.as_table_iter().map(|row|row.map(|bead|bead.color).collect()).collect()
+    }
+
+    fn size(width: usize, height: usize) -> Size {
+        Size {
+            width: NonZeroUsize::new(width).unwrap(),
+            height: NonZeroUsize::new(height).unwrap(),
+        }
+    }
+
+    #[test]
+    fn png_import() {
+        // 4×2 RGBA: left half red, right half blue, a transparent pixel
+        let mut data = Vec::new();
+        for row in 0..2 {
+            for column in 0..4 {
+                let alpha = if row == 1 && column == 3 { 0 } else { 255 };
+                data.extend_from_slice(&if column < 2 { [255, 0, 0, alpha] } else { [0, 0, 255, alpha] });
+            }
+        }
+        let mut png_data = Vec::new();
+        {
+            let mut encoder = png::Encoder::new(&mut png_data, 4, 2);
+            encoder.set_color(png::ColorType::RGBA);
+            encoder.set_depth(png::BitDepth::Eight);
+            encoder.write_header().unwrap().write_image_data(&data).unwrap();
+        }
+        let picture = Picture::from_png(&png_data).unwrap();
+        assert_eq!(picture.pixels[7], Color::default());
+
+        let red = Color {r: 255, g: 0, b: 0};
+        let blue = Color {r: 0, g: 0, b: 255};
+        let options = ImageOptions {size: size(2, 1), keep_aspect: false, quantization: Quantization::Colors(2)};
+        // the transparent pixel lightens the blue half
+        let light_blue = Color {r: 63, g: 63, b: 255};
+        assert_eq!(colors(&picture.to_grid(options, Schema::Straight, &[])), vec![vec![red, light_blue]]);
+
+        let options = ImageOptions {size: size(4, 1), keep_aspect: true, quantization: Quantization::Palette};
+        let grid = picture.to_grid(options, Schema::Straight, &[red, blue, Color::default()]);
+        assert_eq!(colors(&grid), vec![vec![red, red, blue, blue], vec![red, red, blue, Color::default()]]);
+        assert_eq!(picture.rows(40, Schema::FirstOffset), 23);
+    }
+
+    #[test]
+    fn bmp_import() {
+        // 2×2, 24 bits, bottom-up rows padded to 8 bytes
+        let mut data = vec![0u8; 54];
+        data[0..2].copy_from_slice(b"BM");
+        data[10..14].copy_from_slice(&54u32.to_le_bytes());
+        data[14..18].copy_from_slice(&40u32.to_le_bytes());
+        data[18..22].copy_from_slice(&2i32.to_le_bytes());
+        data[22..26].copy_from_slice(&2i32.to_le_bytes());
+        data[28..30].copy_from_slice(&24u16.to_le_bytes());
+        data.extend_from_slice(&[255, 0, 0, 0, 255, 0, 0, 0]);
+        data.extend_from_slice(&[0, 0, 255, 255, 255, 255, 0, 0]);
+        let picture = Picture::from_bmp(&data).unwrap();
+        assert_eq!(picture.pixels, vec![
+            Color {r: 255, g: 0, b: 0}, Color {r: 255, g: 255, b: 255},
+            Color {r: 0, g: 0, b: 255}, Color {r: 0, g: 255, b: 0},
+        ]);
+        data[28..30].copy_from_slice(&4u16.to_le_bytes());
+        assert!(Picture::from_bmp(&data).unwrap_err().starts_with("Unsupported BMP"));
+
+        data[28..30].copy_from_slice(&24u16.to_le_bytes());
+        for (width, height) in [(i32::MAX, i32::MAX), (2, i32::MIN), (2, 3)] {
+            data[18..22].copy_from_slice(&width.to_le_bytes());
+            data[22..26].copy_from_slice(&height.to_le_bytes());
+            assert_eq!(Picture::from_bmp(&data).unwrap_err(), "Broken BMP");
+        }
+    }
+}
@@ -1,56 +1,89 @@
-use crate::model::{Grid, ColorBead, Color, Bead};
-use std::fs::File;
-use std::io::{Write, BufReader};
-use quick_csv::Csv;
-use std::str::FromStr;
-use std::num::NonZeroUsize;
-use std::path::{Path, PathBuf};
-use crate::model::beads::BeadsLine;
-use serde::Deserialize;
-
-pub fn save(path: &PathBuf, line: &BeadsLine<ColorBead>) -> Result<(), String> {
-    let mut file = File::create(path)
-        .map_err(|e|e.to_string())?;
-    let serialized = serde_json::to_string(line)
-        .map_err(|e|e.to_string())?;
-    file.write_all(serialized.as_bytes())
-        .map_err(|e|e.to_string())
-}
+use std::path::PathBuf;
+
+mod document;
+mod csv;
+mod image;
+mod chart;
+mod sheet;
+mod stringing;
+mod catalog;
+mod palette;
+mod settings;
+
+pub use document::{Document, save, load};
+pub use self::csv::{load_grid, save_grid, save_line};
+pub use image::{load_image, ImageOptions, Quantization};
+pub use chart::{save_chart, ChartOptions, MAX_BEAD_SIZE, MIN_BEAD_SIZE};
+pub use sheet::{save_sheet_pdf, save_sheet_svg};
+pub use stringing::{save_stringing, TextOptions};
+pub use catalog::{load_catalog, Catalog};
+pub use self::palette::{load_palette, save_palette, PaletteFormat};
+pub use settings::{save_default_palette, user_palette, UserSettings};
 
-pub fn load_line(path: &PathBuf) -> Result<BeadsLine<ColorBead>, String> {
-    let file = File::open(path).map_err(|e| e.to_string())?;
-    let reader = BufReader::new(file);
-    let mut deserializer = serde_json::Deserializer::from_reader(reader);
-    BeadsLine::deserialize(&mut deserializer).map_err(|e|e.to_string())
+/// Formats the pattern can be saved to
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum FileType {
+    #[default]
+    Pattern,
+    /// colors of the grid cells, the format read by `load_grid`
+    GridCsv,
+    /// colors and counts of the bead line items
+    LineCsv,
+    /// stringing order of the bead line items
+    LineText,
+    /// picture of the grid as it is shown
+    Chart,
+    /// printable pages with the chart, the legend and the stringing list
+    SheetSvg,
+    SheetPdf,
+    /// palette colors with their names
+    PaletteGpl,
+    PalettePaintNet,
+    PaletteJson,
 }
 
+impl FileType {
+    pub const ALL: [FileType; 10] = [
+        FileType::Pattern, FileType::GridCsv, FileType::LineCsv, FileType::LineText,
+        FileType::Chart, FileType::SheetSvg, FileType::SheetPdf,
+        FileType::PaletteGpl, FileType::PalettePaintNet, FileType::PaletteJson,
+    ];
 
-pub fn load_grid<T: AsRef<Path>>(file: T) -> Result<Grid<ColorBead>, String> {
-    let mut data = Vec::with_capacity(10000usize);
-    let csv = Csv::from_file(file).map_err(|e|e.to_string())?;
-    let mut first = true;
-    let mut width = 0usize;
-    for row in csv.into_iter() {
-        let row = row.map_err(|e|e.to_string())?;
-        if first {
-            first = false;
-            width = row.len();
+    pub fn name(self) -> &'static str {
+        match self {
+            FileType::Pattern => "Pattern",
+            FileType::GridCsv => "Grid CSV",
+            FileType::LineCsv => "Line CSV",
+            FileType::LineText => "Stringing text",
+            FileType::Chart => "PNG chart",
+            FileType::SheetSvg => "SVG sheet",
+            FileType::SheetPdf => "PDF sheet",
+            FileType::PaletteGpl => "GIMP palette",
+            FileType::PalettePaintNet => "Paint.NET palette",
+            FileType::PaletteJson => "JSON palette",
+        }
+    }
+    pub fn extension(self) -> &'static str {
+        match self {
+            FileType::Pattern | FileType::PaletteJson => "json",
+            FileType::GridCsv | FileType::LineCsv => "csv",
+            FileType::LineText | FileType::PalettePaintNet => "txt",
+            FileType::Chart => "png",
+            FileType::SheetSvg => "svg",
+            FileType::SheetPdf => "pdf",
+            FileType::PaletteGpl => "gpl",
         }
-        row.columns().map_err(|e|e.to_string())?.for_each(|item| {
-            data.push(Color::from_str(item).unwrap())
-        })
     }
-    let width = NonZeroUsize::new(width).ok_or("invalid width".to_string())?;
-    let data = data.into_iter().map(|color|(color, false)).collect();
-    let grid = Grid::frow_raw(width, data)
-        .map_err(|e|e.to_string())?
-        .map(|item|Bead {
-        color: item.clone(),
-        filled: false,
-    });
-    Ok(grid)
+}
+
+/// Settings of the file types other than the pattern
+#[derive(Debug, Copy, Clone, Default)]
+pub struct ExportOptions {
+    pub chart: ChartOptions,
+    pub text: TextOptions,
 }
 
 pub fn default_dir() -> PathBuf {
     dirs::document_dir().unwrap_or(".".into())
-}
\ No newline at end of file
+}
+
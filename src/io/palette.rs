@@ -0,0 +1,222 @@
+use crate::model::{Color, ColorInfo, Palette};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use serde::{Serialize, Deserialize};
+
+/// Marker of palette files of the JSON format
+pub const PALETTE_FORMAT: &str = "beads-and-threads-palette";
+pub const PALETTE_VERSION: u64 = 1;
+
+const DEFAULT: &str = include_str!("../../resources/palettes/default.gpl");
+
+/// Palette file formats of other applications and the own one
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PaletteFormat {
+    /// GIMP and Inkscape palette
+    Gpl,
+    /// Paint.NET palette, colors have no names there
+    PaintNet,
+    Json,
+}
+
+impl PaletteFormat {
+    /// The format of the file extension, the own format is the default
+    pub fn of<T: AsRef<Path>>(path: T) -> Self {
+        let extension = path.as_ref().extension()
+            .map(|extension|extension.to_string_lossy().to_lowercase());
+        match extension.as_deref() {
+            Some("gpl") => PaletteFormat::Gpl,
+            Some("txt") => PaletteFormat::PaintNet,
+            _ => PaletteFormat::Json,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct PaletteFile {
+    format: String,
+    version: u64,
+    #[serde(default)]
+    name: String,
+    colors: Vec<Entry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    color: String,
+    #[serde(flatten)]
+    info: ColorInfo,
+}
+
+/// Palette shown on start
+pub fn default_palette() -> Palette<Color> {
+    parse_palette(DEFAULT, PaletteFormat::Gpl).expect("bundled palette is valid")
+}
+
+/// Colors in the palette order, the name is written when the format supports it
+pub fn palette_text(palette: &Palette<Color>, name: &str, format: PaletteFormat) -> Result<String, String> {
+    let colors = palette.colors();
+    Ok(match format {
+        PaletteFormat::Gpl => {
+            let mut text = format!("GIMP Palette\nName: {}\nColumns: 8\n#\n", name);
+            for color in colors {
+                let label = palette.info(color).map(ColorInfo::label).unwrap_or_else(||color.to_string());
+                text += &format!("{:3} {:3} {:3}\t{}\n", color.r, color.g, color.b, label);
+            }
+            text
+        },
+        PaletteFormat::PaintNet => {
+            let mut text = format!("; paint.net Palette File\n; Palette: {}\n; Colors: {}\n", name, colors.len());
+            for color in colors {
+                text += &format!("FF{:02X}{:02X}{:02X}\n", color.r, color.g, color.b);
+            }
+            text
+        },
+        PaletteFormat::Json => {
+            let file = PaletteFile {
+                format: PALETTE_FORMAT.to_string(),
+                version: PALETTE_VERSION,
+                name: name.to_string(),
+                colors: colors.iter()
+                    .map(|color|Entry {
+                        color: color.to_string(),
+                        info: palette.info(color).cloned().unwrap_or_default(),
+                    })
+                    .collect(),
+            };
+            serde_json::to_string_pretty(&file).map_err(|e|e.to_string())?
+        },
+    })
+}
+
+fn line_error(number: usize, line: &str) -> String {
+    format!("Palette line {}: wrong color \"{}\"", number + 1, line.trim())
+}
+
+/// The first color is activated
+pub fn parse_palette(text: &str, format: PaletteFormat) -> Result<Palette<Color>, String> {
+    let mut colors: Vec<(Color, ColorInfo)> = Vec::new();
+    match format {
+        PaletteFormat::Gpl => {
+            let mut lines = text.lines().enumerate();
+            if lines.next().map(|(_, line)|line.trim()) != Some("GIMP Palette") {
+                return Err("Not a GIMP palette file".to_string());
+            }
+            for (number, line) in lines {
+                let trimmed = line.trim();
+                if trimmed.is_empty() || trimmed.starts_with('#')
+                    || trimmed.starts_with("Name:") || trimmed.starts_with("Columns:") {
+                    continue;
+                }
+                let mut parts = trimmed.split_whitespace();
+                let mut channel = || parts.next()
+                    .and_then(|part|part.parse::<u8>().ok())
+                    .ok_or_else(||line_error(number, line));
+                let color = Color {r: channel()?, g: channel()?, b: channel()?};
+                let name = parts.collect::<Vec<_>>().join(" ");
+                // names made up by editors are not worth keeping
+                let info = if name.is_empty() || name == "Untitled" || name.parse::<Color>().ok() == Some(color) {
+                    ColorInfo::default()
+                } else {
+                    ColorInfo {name, ..Default::default()}
+                };
+                colors.push((color, info));
+            }
+        },
+        PaletteFormat::PaintNet => {
+            for (number, line) in text.lines().enumerate() {
+                let trimmed = line.trim();
+                if trimmed.is_empty() || trimmed.starts_with(';') {
+                    continue;
+                }
+                // AARRGGBB, the transparency is dropped
+                let rgb = if trimmed.len() == 8 && trimmed.is_ascii() { &trimmed[2..] } else { trimmed };
+                let color = rgb.parse::<Color>().ok()
+                    .filter(|_|rgb.len() == 6)
+                    .ok_or_else(||line_error(number, line))?;
+                colors.push((color, ColorInfo::default()));
+            }
+        },
+        PaletteFormat::Json => {
+            let file: PaletteFile = serde_json::from_str(text)
+                .map_err(|e|format!("Broken palette file: {}", e))?;
+            if file.format != PALETTE_FORMAT {
+                return Err("Not a palette file".to_string());
+            }
+            if file.version > PALETTE_VERSION {
+                return Err(format!(
+                    "The palette is made for a newer version of the application (palette format {}, supported {})",
+                    file.version, PALETTE_VERSION
+                ));
+            }
+            for (i, Entry {color, info}) in file.colors.into_iter().enumerate() {
+                let parsed = color.parse::<Color>()
+                    .map_err(|_|format!("Palette color {}: wrong color \"{}\"", i + 1, color))?;
+                colors.push((parsed, info));
+            }
+        },
+    }
+    if colors.is_empty() {
+        return Err("The palette has no colors".to_string());
+    }
+    let mut palette = Palette::from(colors.iter().map(|(color, _)|*color).collect::<Vec<_>>());
+    colors.into_iter().for_each(|(color, info)|palette.set_info(color, info));
+    Ok(palette)
+}
+
+/// The format is chosen by the extension
+pub fn load_palette<T: AsRef<Path>>(path: T) -> Result<Palette<Color>, String> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path)
+        .map_err(|e|format!("Unable to read {}: {}", path.display(), e))?;
+    parse_palette(&text, PaletteFormat::of(path))
+}
+
+/// The file name becomes the palette name
+pub fn save_palette<T: AsRef<Path>>(path: T, palette: &Palette<Color>, format: PaletteFormat) -> Result<(), String> {
+    let path = path.as_ref();
+    let name = path.file_stem().map(|name|name.to_string_lossy().into_owned()).unwrap_or_default();
+    let text = palette_text(palette, &name, format)?;
+    let mut file = File::create(path).map_err(|e|e.to_string())?;
+    file.write_all(text.as_bytes()).map_err(|e|e.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn formats() {
+        assert!(default_palette().colors().len() > 10);
+
+        let black = Color {r: 0, g: 0, b: 0};
+        let red = Color {r: 200, g: 10, b: 20};
+        let mut palette = Palette::from(vec![red, black, Color::default()]);
+        palette.set_info(black, ColorInfo {name: "Black".to_string(), code: "DB-10".to_string(), ..Default::default()});
+
+        for &format in &[PaletteFormat::Gpl, PaletteFormat::PaintNet, PaletteFormat::Json] {
+            let text = palette_text(&palette, "Test", format).unwrap();
+            let parsed = parse_palette(&text, format).unwrap();
+            assert_eq!(parsed.colors(), palette.colors(), "{:?}", format);
+            assert_eq!(parsed.activated(), &red);
+            assert_eq!(parsed.info(&red), None);
+            let info = parsed.info(&black).cloned().unwrap_or_default();
+            match format {
+                PaletteFormat::Gpl => assert_eq!(info.name, "DB-10 Black"),
+                PaletteFormat::PaintNet => assert!(info.is_empty()),
+                PaletteFormat::Json => assert_eq!(&info, palette.info(&black).unwrap()),
+            }
+        }
+
+        let gpl = "GIMP Palette\nName: x\n#\n255 255 255\tUntitled\n  0 128 255 Sky blue\n";
+        let parsed = parse_palette(gpl, PaletteFormat::Gpl).unwrap();
+        assert_eq!(parsed.colors()[1], Color {r: 0, g: 128, b: 255});
+        assert_eq!(parsed.info(&Color::default()), None);
+        assert_eq!(parsed.info(&parsed.colors()[1]).unwrap().name, "Sky blue");
+        assert_eq!(parse_palette("GIMP Palette\n1 2\n", PaletteFormat::Gpl).unwrap_err(), "Palette line 2: wrong color \"1 2\"");
+        assert!(parse_palette("; nothing\n", PaletteFormat::PaintNet).is_err());
+        assert_eq!(parse_palette("ff00ff", PaletteFormat::PaintNet).unwrap().colors(), &[Color {r: 255, g: 0, b: 255}]);
+        assert_eq!(PaletteFormat::of("a/b.GPL"), PaletteFormat::Gpl);
+    }
+}
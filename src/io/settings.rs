@@ -0,0 +1,103 @@
+use crate::model::{Color, Palette, Size, ViewState};
+use super::palette::{default_palette, load_palette, save_palette, PaletteFormat};
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+use serde::{Serialize, Deserialize};
+
+const SETTINGS_FILE: &str = "settings.json";
+const PALETTE_FILE: &str = "palette.json";
+
+/// Values kept between launches, missing ones take the defaults
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UserSettings {
+    /// window width and height
+    pub window: (u32, u32),
+    /// grid size of the pattern shown on start
+    pub size: Size,
+    /// half of the bead size in pixels
+    pub zoom: u16,
+    /// directory of the last opened or saved file
+    pub last_dir: Option<PathBuf>,
+}
+
+impl Default for UserSettings {
+    fn default() -> Self {
+        Self {
+            window: (550, 480),
+            size: Size::default(),
+            zoom: ViewState::default().zoom,
+            last_dir: None,
+        }
+    }
+}
+
+/// Directory of the application in the user config directory
+fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir|dir.join("beads-and-threads"))
+}
+
+fn config_file(name: &str) -> Result<PathBuf, String> {
+    let dir = config_dir().ok_or_else(||"Unable to find the config directory".to_string())?;
+    std::fs::create_dir_all(&dir).map_err(|e|format!("Unable to create {}: {}", dir.display(), e))?;
+    Ok(dir.join(name))
+}
+
+impl UserSettings {
+    /// A missing or broken file gives the defaults, the app starts anyway
+    pub fn load() -> Self {
+        config_dir()
+            .and_then(|dir|std::fs::read_to_string(dir.join(SETTINGS_FILE)).ok())
+            .and_then(|text|serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let text = serde_json::to_string_pretty(self).map_err(|e|e.to_string())?;
+        let mut file = File::create(config_file(SETTINGS_FILE)?).map_err(|e|e.to_string())?;
+        file.write_all(text.as_bytes()).map_err(|e|e.to_string())
+    }
+
+    /// The last directory while it exists
+    pub fn dir(&self) -> PathBuf {
+        self.last_dir.clone()
+            .filter(|dir|dir.is_dir())
+            .unwrap_or_else(super::default_dir)
+    }
+}
+
+/// Palette saved by the user as the default one, or the bundled palette
+pub fn user_palette() -> Palette<Color> {
+    config_dir()
+        .and_then(|dir|load_palette(dir.join(PALETTE_FILE)).ok())
+        .unwrap_or_else(default_palette)
+}
+
+pub fn save_default_palette(palette: &Palette<Color>) -> Result<(), String> {
+    save_palette(config_file(PALETTE_FILE)?, palette, PaletteFormat::Json)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::num::NonZeroUsize;
+
+    #[test]
+    fn settings() {
+        let settings = UserSettings {
+            window: (800, 600),
+            size: Size {width: NonZeroUsize::new(10).unwrap(), height: NonZeroUsize::new(20).unwrap()},
+            zoom: 3,
+            // no directory can have the zero byte in its name
+            last_dir: Some("no\0such directory".into()),
+        };
+        let text = serde_json::to_string(&settings).unwrap();
+        assert_eq!(serde_json::from_str::<UserSettings>(&text).unwrap(), settings);
+
+        let partial: UserSettings = serde_json::from_str("{\"zoom\": 9}").unwrap();
+        assert_eq!(partial, UserSettings {zoom: 9, ..Default::default()});
+        assert!(serde_json::from_str::<UserSettings>("{\"size\": {\"width\": 0, \"height\": 5}}").is_err());
+        assert_eq!(settings.dir(), crate::io::default_dir());
+    }
+}
@@ -0,0 +1,393 @@
+use crate::model::{Model, Color, Bead, Schema};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+/// A4 in points
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 36.0;
+/// space under the content for the page number
+const FOOTER: f32 = 20.0;
+const CONTENT_WIDTH: f32 = PAGE_WIDTH - 2.0 * MARGIN;
+
+const BLACK: Color = Color {r: 0, g: 0, b: 0};
+const GRAY: Color = Color {r: 0x80, g: 0x80, b: 0x80};
+const MARKER: Color = Color {r: 230, g: 0, b: 0};
+
+const MAX_BEAD: f32 = 14.0;
+const ROW_NUMBERS: f32 = 24.0;
+const LINE: f32 = 16.0;
+const LIST_COLUMN: f32 = 100.0;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Anchor { Start, Middle, End }
+
+#[derive(Debug, Clone)]
+enum Shape {
+    Rect {x: f32, y: f32, width: f32, height: f32, fill: Option<Color>, stroke: Option<Color>},
+    /// `y` is the baseline
+    Text {x: f32, y: f32, size: f32, color: Color, anchor: Anchor, text: String},
+}
+
+#[derive(Debug, Default)]
+struct Page {
+    shapes: Vec<Shape>,
+}
+
+impl Page {
+    fn rect(&mut self, x: f32, y: f32, width: f32, height: f32, fill: Option<Color>, stroke: Option<Color>) {
+        self.shapes.push(Shape::Rect {x, y, width, height, fill, stroke});
+    }
+    fn text<T: Into<String>>(&mut self, x: f32, y: f32, size: f32, anchor: Anchor, text: T) {
+        self.colored_text(x, y, size, BLACK, anchor, text);
+    }
+    fn colored_text<T: Into<String>>(&mut self, x: f32, y: f32, size: f32, color: Color, anchor: Anchor, text: T) {
+        self.shapes.push(Shape::Text {x, y, size, color, anchor, text: text.into()});
+    }
+    /// Color box with the symbol written over it
+    fn swatch(&mut self, x: f32, y: f32, size: f32, color: Color, symbol: &str) {
+        self.rect(x, y, size, size, Some(color), Some(GRAY));
+        if size >= 6.0 {
+            let text_size = size * 0.65;
+            self.colored_text(x + size / 2.0, y + (size + text_size * 0.7) / 2.0, text_size, contrast(color), Anchor::Middle, symbol);
+        }
+    }
+}
+
+/// Black or white, whichever is readable over the color
+fn contrast(Color {r, g, b}: Color) -> Color {
+    let luminance = 0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32;
+    if luminance < 128.0 { Color {r: 255, g: 255, b: 255} } else { BLACK }
+}
+
+/// Places blocks one under another, starting new pages when they do not fit
+struct Layout {
+    pages: Vec<Page>,
+    y: f32,
+}
+
+impl Layout {
+    fn new() -> Self {
+        Self {
+            pages: vec![Page::default()],
+            y: MARGIN,
+        }
+    }
+    fn page(&mut self) -> &mut Page {
+        self.pages.last_mut().expect("layout has a page")
+    }
+    fn new_page(&mut self) {
+        self.pages.push(Page::default());
+        self.y = MARGIN;
+    }
+    fn space_left(&self) -> f32 {
+        PAGE_HEIGHT - MARGIN - FOOTER - self.y
+    }
+    /// Top of a block of the given height
+    fn place(&mut self, height: f32) -> f32 {
+        if height > self.space_left() {
+            self.new_page();
+        }
+        let top = self.y;
+        self.y += height;
+        top
+    }
+    fn heading(&mut self, text: &str) {
+        // a heading is not left alone at the bottom of a page
+        if self.space_left() < 3.0 * LINE {
+            self.new_page();
+        }
+        let top = self.place(LINE * 1.5) + LINE;
+        self.page().text(MARGIN, top, 13.0, Anchor::Start, text);
+    }
+}
+
+/// Printable pages of the pattern: the chart with the color symbols, the legend and the stringing list
+pub struct Sheet {
+    pages: Vec<Page>,
+}
+
+impl Sheet {
+    pub fn new(title: &str, model: &Model<Color>, rotation: isize) -> Self {
+        let grid = model.grid();
+        let line = model.line();
+        let legend = model.legend();
+        let symbol = |color: &Color| model.symbols().get(color);
+        let (width, height) = (grid.width(), grid.size().height());
+        let mut layout = Layout::new();
+
+        let top = layout.place(2.0 * LINE);
+        layout.page().text(MARGIN, top + LINE, 16.0, Anchor::Start, title);
+        let total: usize = legend.iter().map(|&(_, count, _)|count).sum();
+        let top = layout.place(LINE);
+        layout.page().text(MARGIN, top + LINE * 0.75, 10.0, Anchor::Start, format!(
+            "Width: {}, rows: {}, beads: {}, colors: {}", width, height, total, legend.len()
+        ));
+
+        layout.heading("Chart");
+        let bead = (CONTENT_WIDTH - ROW_NUMBERS) / (width as f32 + 1.5);
+        let bead = bead.min(MAX_BEAD);
+        let portions = match model.schema() {
+            Schema::FirstOffset => [bead, bead / 2.0],
+            Schema::SecondOffset => [bead / 2.0, bead],
+            Schema::Straight => [bead / 2.0, bead / 2.0],
+        };
+        let rotation = rotation.rem_euclid(width as isize) as usize;
+        for (index, row) in grid.as_full_table_iter().enumerate() {
+            let y = layout.place(bead);
+            let page = layout.page();
+            page.text(MARGIN + ROW_NUMBERS - 4.0, y + bead * 0.8, bead.min(9.0), Anchor::End, (index + 1).to_string());
+            let left = MARGIN + ROW_NUMBERS + portions[index % 2];
+            for (position, (Bead {color, ..}, first)) in row.cycle().skip(rotation).take(width).enumerate() {
+                let x = left + position as f32 * bead;
+                page.swatch(x, y, bead, *color, symbol(color));
+                if *first {
+                    page.rect(x, y, bead, bead, None, Some(MARKER));
+                }
+            }
+        }
+
+        layout.heading("Colors");
+        for &(color, count, symbol) in &legend {
+            let y = layout.place(LINE);
+            let page = layout.page();
+            page.swatch(MARGIN, y + 2.0, 12.0, color, symbol);
+            page.text(MARGIN + 20.0, y + 12.0, 10.0, Anchor::Start, color.to_string());
+            page.text(MARGIN + 120.0, y + 12.0, 10.0, Anchor::End, count.to_string());
+            if let Some(info) = model.palette().info(&color) {
+                page.text(MARGIN + 135.0, y + 12.0, 10.0, Anchor::Start, info.label());
+            }
+        }
+
+        layout.heading("Stringing list");
+        let columns = (CONTENT_WIDTH / LIST_COLUMN) as usize;
+        let mut items = line.line().iter().enumerate().peekable();
+        while items.peek().is_some() {
+            let rows = (layout.space_left() / LINE) as usize;
+            if rows == 0 {
+                layout.new_page();
+                continue;
+            }
+            let top = layout.y;
+            let mut used = 0;
+            for column in 0..columns {
+                for (row, (index, (Bead {color, filled}, count))) in items.by_ref().take(rows).enumerate() {
+                    let x = MARGIN + column as f32 * LIST_COLUMN;
+                    let y = top + row as f32 * LINE;
+                    let page = layout.page();
+                    page.text(x + 28.0, y + 12.0, 10.0, Anchor::End, format!("{}.", index + 1));
+                    page.rect(x + 32.0, y + 4.0, 8.0, 8.0, if *filled { Some(GRAY) } else { None }, Some(BLACK));
+                    page.swatch(x + 44.0, y + 2.0, 12.0, *color, symbol(color));
+                    page.text(x + 60.0, y + 12.0, 10.0, Anchor::Start, format!("x {}", count));
+                    used = used.max(row + 1);
+                }
+            }
+            layout.y = top + used as f32 * LINE;
+            if items.peek().is_some() {
+                layout.new_page();
+            }
+        }
+
+        let mut pages = layout.pages;
+        let count = pages.len();
+        for (index, page) in pages.iter_mut().enumerate() {
+            page.text(PAGE_WIDTH / 2.0, PAGE_HEIGHT - MARGIN, 9.0, Anchor::Middle, format!("{} / {}", index + 1, count));
+        }
+        Self {pages}
+    }
+
+    /// Single picture with the pages placed one under another
+    pub fn svg(&self) -> String {
+        let mut result = format!(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+            <svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}pt\" height=\"{h}pt\" viewBox=\"0 0 {w} {h}\" \
+            font-family=\"Helvetica, Arial, sans-serif\">\n",
+            w = PAGE_WIDTH, h = PAGE_HEIGHT * self.pages.len() as f32
+        );
+        for (index, page) in self.pages.iter().enumerate() {
+            result += &format!("<g transform=\"translate(0 {})\">\n", PAGE_HEIGHT * index as f32);
+            result += &format!("<rect width=\"{}\" height=\"{}\" fill=\"#FFFFFF\" stroke=\"#C0C0C0\"/>\n", PAGE_WIDTH, PAGE_HEIGHT);
+            for shape in &page.shapes {
+                result += &match shape {
+                    Shape::Rect {x, y, width, height, fill, stroke} => format!(
+                        "<rect x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" height=\"{:.2}\" fill=\"{}\" stroke=\"{}\" stroke-width=\"0.5\"/>\n",
+                        x, y, width, height,
+                        fill.map(|color|color.to_string()).unwrap_or_else(||"none".to_string()),
+                        stroke.map(|color|color.to_string()).unwrap_or_else(||"none".to_string()),
+                    ),
+                    Shape::Text {x, y, size, color, anchor, text} => format!(
+                        "<text x=\"{:.2}\" y=\"{:.2}\" font-size=\"{:.2}\" fill=\"{}\" text-anchor=\"{}\">{}</text>\n",
+                        x, y, size, color,
+                        match anchor { Anchor::Start => "start", Anchor::Middle => "middle", Anchor::End => "end" },
+                        escape_xml(text),
+                    ),
+                };
+            }
+            result += "</g>\n";
+        }
+        result += "</svg>\n";
+        result
+    }
+
+    pub fn pdf(&self) -> Vec<u8> {
+        let mut objects = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                (0..self.pages.len()).map(|i|format!("{} 0 R", 4 + 2 * i)).collect::<Vec<_>>().join(" "),
+                self.pages.len()
+            ),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>".to_string(),
+        ];
+        for (index, page) in self.pages.iter().enumerate() {
+            let content = pdf_content(page);
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+                PAGE_WIDTH, PAGE_HEIGHT, 5 + 2 * index
+            ));
+            objects.push(format!("<< /Length {} >>\nstream\n{}endstream", content.len(), content));
+        }
+        let mut result = "%PDF-1.4\n".to_string();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (index, object) in objects.iter().enumerate() {
+            offsets.push(result.len());
+            result += &format!("{} 0 obj\n{}\nendobj\n", index + 1, object);
+        }
+        let xref = result.len();
+        result += &format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            result += &format!("{:010} 00000 n \n", offset);
+        }
+        result += &format!("trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n", objects.len() + 1, xref);
+        result.into_bytes()
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn pdf_color(Color {r, g, b}: Color) -> String {
+    format!("{:.3} {:.3} {:.3}", r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0)
+}
+
+/// Drawing operators of the page, PDF coordinates grow upwards
+fn pdf_content(page: &Page) -> String {
+    let mut content = "0.5 w\n".to_string();
+    for shape in &page.shapes {
+        match shape {
+            Shape::Rect {x, y, width, height, fill, stroke} => {
+                let rect = format!("{:.2} {:.2} {:.2} {:.2} re", x, PAGE_HEIGHT - y - height, width, height);
+                let operator = match (fill, stroke) {
+                    (Some(_), Some(_)) => "B",
+                    (Some(_), None) => "f",
+                    (None, Some(_)) => "S",
+                    (None, None) => continue,
+                };
+                fill.map(|color|content += &format!("{} rg\n", pdf_color(color)));
+                stroke.map(|color|content += &format!("{} RG\n", pdf_color(color)));
+                content += &format!("{} {}\n", rect, operator);
+            },
+            Shape::Text {x, y, size, color, anchor, text} => {
+                // Helvetica letters and digits are a bit wider than a half of the font size
+                let width = text.chars().count() as f32 * size * 0.56;
+                let x = match anchor {
+                    Anchor::Start => *x,
+                    Anchor::Middle => x - width / 2.0,
+                    Anchor::End => x - width,
+                };
+                let escaped: String = text.chars()
+                    .filter(char::is_ascii)
+                    .flat_map(|c| match c {
+                        '(' | ')' | '\\' => vec!['\\', c],
+                        c => vec![c],
+                    })
+                    .collect();
+                content += &format!(
+                    "{} rg\nBT /F1 {:.2} Tf {:.2} {:.2} Td ({}) Tj ET\n",
+                    pdf_color(*color), size, x, PAGE_HEIGHT - y, escaped
+                );
+            },
+        }
+    }
+    content
+}
+
+pub fn save_sheet_svg<T: AsRef<Path>>(path: T, model: &Model<Color>, rotation: isize) -> Result<(), String> {
+    let sheet = Sheet::new(&title(path.as_ref()), model, rotation);
+    let mut file = File::create(path).map_err(|e|e.to_string())?;
+    file.write_all(sheet.svg().as_bytes()).map_err(|e|e.to_string())
+}
+
+pub fn save_sheet_pdf<T: AsRef<Path>>(path: T, model: &Model<Color>, rotation: isize) -> Result<(), String> {
+    let sheet = Sheet::new(&title(path.as_ref()), model, rotation);
+    let mut file = File::create(path).map_err(|e|e.to_string())?;
+    file.write_all(&sheet.pdf()).map_err(|e|e.to_string())
+}
+
+fn title(path: &Path) -> String {
+    path.file_stem()
+        .map(|name|name.to_string_lossy().into_owned())
+        .unwrap_or_else(||"Pattern".to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::model::Size;
+    use std::num::NonZeroUsize;
+
+    fn texts(sheet: &Sheet) -> Vec<String> {
+        sheet.pages.iter()
+            .flat_map(|page|page.shapes.iter())
+            .filter_map(|shape| match shape {
+                Shape::Text {text, ..} => Some(text.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sheet() {
+        let black = Color {r: 0, g: 0, b: 0};
+        let mut model = Model::default();
+        model.activate_color(black);
+        model.set(0, 0).unwrap();
+        let sheet = Sheet::new("test", &model, 0);
+        let written = texts(&sheet);
+        assert!(written.contains(&"test".to_string()));
+        assert!(written.contains(&black.to_string()));
+        assert!(written.contains(&"A".to_string()));
+        assert!(written.contains(&"B".to_string()));
+        assert!(written.contains(&format!("1 / {}", sheet.pages.len())));
+
+        // every other bead differs, so the stringing list runs over several pages
+        let mut model = Model::default();
+        model.resize(Size {width: NonZeroUsize::new(20).unwrap(), height: NonZeroUsize::new(40).unwrap()});
+        model.activate_color(black);
+        for row in 0..40 {
+            for column in (0..20).step_by(2) {
+                model.set(row, column).unwrap();
+            }
+        }
+        let sheet = Sheet::new("test", &model, 0);
+        assert!(sheet.pages.len() > 2);
+        let items = model.line().line().len();
+        assert!(texts(&sheet).contains(&format!("{}.", items)));
+    }
+
+    #[test]
+    fn documents() {
+        let sheet = Sheet::new("a <b>", &Model::default(), 0);
+        let svg = sheet.svg();
+        assert!(svg.contains("<svg") && svg.contains("a &lt;b&gt;"));
+
+        let pdf = String::from_utf8(sheet.pdf()).unwrap();
+        assert!(pdf.starts_with("%PDF-1.4") && pdf.ends_with("%%EOF\n"));
+        let xref: usize = pdf.lines().rev().nth(1).unwrap().parse().unwrap();
+        assert!(pdf[xref..].starts_with("xref"));
+        let first: usize = pdf[xref..].lines().nth(3).unwrap()[..10].parse().unwrap();
+        assert!(pdf[first..].starts_with("1 0 obj"));
+    }
+}
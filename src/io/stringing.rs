@@ -0,0 +1,112 @@
+use crate::model::{Model, Color, Bead};
+use fxhash::FxHashMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+/// Items written on a line of a block
+const ITEMS_PER_LINE: usize = 5;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TextOptions {
+    /// count of the line items in a block
+    pub block: usize,
+    /// skip the items already marked as strung
+    pub unfinished_only: bool,
+}
+
+impl Default for TextOptions {
+    fn default() -> Self {
+        Self {
+            block: 10,
+            unfinished_only: false,
+        }
+    }
+}
+
+/// Stringing order with the symbols of the right panel, items keep their numbers when some are skipped
+pub fn stringing_text(model: &Model<Color>, options: TextOptions) -> String {
+    let line = model.line();
+    let legend = model.legend();
+    let items: Vec<_> = line.line().iter()
+        .enumerate()
+        .filter(|(_, (bead, _))|!(options.unfinished_only && bead.filled))
+        .collect();
+    let total: usize = items.iter().map(|(_, (_, count))|count).sum();
+    // colors are counted in the listed items only, so the totals match the list
+    let mut counts: FxHashMap<Color, usize> = FxHashMap::default();
+    for (_, (Bead {color, ..}, count)) in items.iter() {
+        *counts.entry(*color).or_default() += count;
+    }
+
+    let mut text = format!("Width: {}\n\nColors:\n", line.width);
+    for (color, _, symbol) in legend {
+        let count = counts.get(&color).copied().unwrap_or_default();
+        if count == 0 {
+            continue;
+        }
+        text += &format!("  {} {} ×{}", symbol, color, count);
+        if let Some(info) = model.palette().info(&color) {
+            text += &format!("  {}", info.label());
+        }
+        text += "\n";
+    }
+    text += &format!("\nStringing order: {} items, {} beads", items.len(), total);
+    if options.unfinished_only {
+        text += ", strung items are skipped";
+    }
+    text += "\n";
+
+    let mut strung = 0;
+    for (number, block) in items.chunks(options.block.max(1)).enumerate() {
+        text += &format!("\nBlock {}\n", number + 1);
+        for row in block.chunks(ITEMS_PER_LINE) {
+            let row: Vec<_> = row.iter()
+                .map(|(index, (Bead {color, ..}, count))|format!(
+                    "{}. {} ×{}", index + 1, model.symbols().get(color), count
+                ))
+                .collect();
+            text += &format!("  {}\n", row.join("  "));
+        }
+        let beads: usize = block.iter().map(|(_, (_, count))|count).sum();
+        strung += beads;
+        text += &format!("  Beads: {}, total: {} of {}\n", beads, strung, total);
+    }
+    text
+}
+
+pub fn save_stringing<T: AsRef<Path>>(path: T, model: &Model<Color>, options: TextOptions) -> Result<(), String> {
+    let mut file = File::create(path).map_err(|e|e.to_string())?;
+    file.write_all(stringing_text(model, options).as_bytes()).map_err(|e|e.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn stringing() {
+        let mut model = Model::default();
+        model.activate_color(Color {r: 0, g: 0, b: 0});
+        model.set(0, 1).unwrap();
+        model.set(0, 3).unwrap();
+        let items = model.line().line().len();
+        model.toggle_filled(0).unwrap();
+
+        let options = TextOptions {block: 2, unfinished_only: false};
+        let text = stringing_text(&model, options);
+        let total = model.size().capacity();
+        assert!(text.contains("Colors:\n  A #FFFFFF ×"));
+        assert!(text.contains("  B #000000 ×2\n"));
+        assert!(text.contains("Block 1\n  1. A ×"));
+        assert!(text.ends_with(&format!(", total: {} of {}\n", total, total)));
+        assert_eq!(text.matches("Block").count(), (items + 1) / 2);
+
+        let text = stringing_text(&model, TextOptions {unfinished_only: true, ..options});
+        assert!(!text.contains(" 1. "));
+        let strung = model.line().line()[0].1;
+        assert!(text.contains(&format!("Colors:\n  A #FFFFFF ×{}\n  B #000000 ×2\n", total - strung - 2)));
+        assert!(text.contains("Block 1\n  2. B ×1  3. A ×1\n"));
+        assert_eq!(text.matches("Block").count(), items / 2);
+    }
+}
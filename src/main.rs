@@ -11,37 +11,11 @@ use reimport::*;
 use message::Message;
 use ui::*;
 use service::AppService;
-use model::{Model, Color};
+use model::{Model, Color, Grid, ViewState};
+use io::UserSettings;
+use iced_native::{Event, keyboard, mouse, window};
 use std::sync::Arc;
 
-fn default_colors() -> Vec<Color> {
-    vec![
-        Color { r: 0x61 ,   g: 0x00,    b: 0x00 },
-        Color { r: 0xff,    g: 0x00,    b: 0x88 },
-        Color { r: 0x98,    g: 0x02,    b: 0x2f },
-        Color { r: 0xcf,    g: 0x32,    b: 0x00 },
-        Color { r: 0xff,    g: 0x32,    b: 0x32 },
-        Color { r: 0xfd,    g: 0x8c,    b: 0x0e },
-        Color { r: 0xff,    g: 0xe6,    b: 0x11 },
-        Color { r: 0xff,    g: 0xfc,    b: 0x72 },
-        Color { r: 0x88,    g: 0x0c,    b: 0x14 },
-        Color { r: 0xb0,    g: 0x5e,    b: 0x07 },
-        Color { r: 0x29,    g: 0x13,    b: 0x9c },
-        Color { r: 0x3f,    g: 0x9b,    b: 0xe3 },
-        Color { r: 0x69,    g: 0xc7,    b: 0xac },
-        Color { r: 0x9a,    g: 0xcc,    b: 0xb0 },
-        Color { r: 0x49,    g: 0x8c,    b: 0x55 },
-        Color { r: 0x00,    g: 0xb1,    b: 0x5a },
-        Color { r: 0x3e,    g: 0xe0,    b: 0x19 },
-        Color { r: 0x8d,    g: 0xe4,    b: 0x6f },
-        Color { r: 0x8c,    g: 0x62,    b: 0xd3 },
-        Color { r: 0xc8,    g: 0xb5,    b: 0xff },
-        Color { r: 0xdd,    g: 0xdd,    b: 0xdd },
-        Color { r: 0x90,    g: 0x93,    b: 0x9e },
-        Color { r: 0x00,    g: 0x00,    b: 0x00 },
-    ]
-}
-
 struct App {
     service: AppService,
     top_menu: TopMenu,
@@ -50,30 +24,59 @@ struct App {
     right_menu: RightMenu,
     left_menu: LeftMenu,
     left_panel: LeftPanel,
+    /// window width and height
+    window: (u32, u32),
+    /// settings as they were last saved
+    settings: UserSettings,
+    /// view of the grid plate last sent to the service
+    view: ViewState,
+    error: Option<String>,
 }
 
-impl Default for App {
-    fn default() -> Self {
-        let model = default_colors().into_iter().fold(Model::default(), |mut model, color| {
+impl App {
+    fn with_settings(settings: UserSettings) -> Self {
+        let grid = Grid::new(settings.size, Default::default());
+        let model = io::user_palette().colors().iter().fold(Model::from(grid), |mut model, &color| {
             model.add_color(color);
             model
         });
         let service = AppService::new(model.clone());
         let model = Arc::new(model);
+        let mut grid_plate = GridPlate::new(model.clone());
+        grid_plate.update(GridMessage::SetView(ViewState {zoom: settings.zoom, ..Default::default()}));
         Self {
             service,
             top_menu: TopMenu::new(model.clone()),
-            grid_plate: GridPlate::new(model.clone()),
             right_panel: RightPanel::new(model.clone()),
             right_menu: RightMenu::default(),
             left_menu: LeftMenu::default(),
-            left_panel: Default::default(),
+            left_panel: LeftPanel::new(settings.size, settings.dir()),
+            // the service starts with the default view, a different one is sent on the first update
+            view: ViewState::default(),
+            grid_plate,
+            window: settings.window,
+            settings,
+            error: None,
+        }
+    }
+
+    /// Window events wherever they happen, not only over the widgets handling them
+    fn listen(event: &Event) -> Option<Message> {
+        match event {
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => Some(Message::MouseRelease),
+            Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => Some(Message::ModifiersChanged(*modifiers)),
+            Event::Window(window::Event::Resized {width, height}) => Some(Message::WindowResized(*width, *height)),
+            _ => None,
         }
     }
-}
 
-impl App {
     fn update_children(&mut self, message: Message) {
+        match &message {
+            Message::Error(error) => self.error = Some(error.clone()),
+            Message::GridUpdated(_) => self.error = None,
+            Message::WindowResized(width, height) => self.window = (*width, *height),
+            _ => {},
+        }
         self.top_menu.update(message.clone().into());
         self.right_menu.update(message.clone().into());
         self.left_menu.update(message.clone().into());
@@ -81,35 +84,64 @@ impl App {
         self.left_panel.update(message.clone().into());
         self.right_panel.update(message.clone().into());
     }
+
+    /// Writes the settings as soon as one of them changes, the app is not told when the window closes
+    fn save_settings(&mut self) {
+        let settings = UserSettings {
+            window: self.window,
+            size: self.left_panel.size(),
+            zoom: self.grid_plate.view_state().zoom,
+            last_dir: Some(self.left_panel.dir().to_path_buf()),
+        };
+        if settings != self.settings {
+            let saved = settings.save();
+            self.settings = settings;
+            if let Err(error) = saved {
+                self.update_children(Message::Error(format!("Unable to save the settings: {}", error)));
+            }
+        }
+    }
 }
 
-impl Sandbox for App {
+impl Application for App {
+    type Executor = executor::Default;
     type Message = Message;
+    type Flags = UserSettings;
 
-    fn new() -> Self {
-        Default::default()
+    fn new(settings: UserSettings) -> (Self, Command<Message>) {
+        (App::with_settings(settings), Command::none())
     }
     fn title(&self) -> String {
         "Beads and threads by Bool".into()
     }
-    fn update(&mut self, message: Message) {
-        if let Some(service_msg) = self.service.process(message.clone().into()) {
+    fn update(&mut self, message: Message) -> Command<Message> {
+        for service_msg in self.service.process(message.clone()) {
             self.update_children(service_msg);
         }
         self.update_children(message.clone());
+        let view = self.grid_plate.view_state();
+        if view != self.view {
+            self.view = view;
+            self.service.process(Message::ViewUpdated(view));
+        }
+        self.save_settings();
+        Command::none()
     }
 
     fn view(&mut self) -> Element<'_, Message> {
         let top = Container::new(self.top_menu.view().map(From::from))
             .height(Length::Units(30));
-        let bottom = Container::new(Text::new(""));
+        let bottom = Container::new(
+            Text::new(self.error.as_deref().unwrap_or(""))
+                .color(iced::Color::from_rgb(0.8, 0.0, 0.0))
+        );
         let left = Container::new(self.left_menu.view().map(From::from))
             .width(Length::Units(30));
         let right = Container::new(self.right_menu.view().map(From::from))
             .width(Length::Units(25));
         let content = Container::new(self.grid_plate.view().map(From::from));
         let row = Row::new().spacing(5)
-            .push(Element::new(ui::MouseListener(Message::MouseRelease)))
+            .push(Element::new(ui::EventListener(App::listen)))
             .width(Length::Fill)
             .height(Length::Fill)
             .push(left)
@@ -126,14 +158,15 @@ impl Sandbox for App {
 }
 
 fn main() {
+    let settings = UserSettings::load();
     App::run(Settings {
         window: iced::window::Settings {
-            size: (550, 480),
+            size: settings.window,
             resizable: true,
             decorations: true,
             ..Default::default()
         },
-        flags: (),
+        flags: settings,
         default_font: None,
         antialiasing: false,
         ..Default::default()
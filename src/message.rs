@@ -8,7 +8,10 @@ use crate::ui::{
     PaletteMessage
 };
 use std::sync::Arc;
-use crate::model::{Model, Color};
+use iced::keyboard::Modifiers;
+use crate::model::{Model, Color, ViewState};
+use crate::service::History;
+use crate::io::Catalog;
 
 type GMsg = GridMessage<Model<Color>>;
 
@@ -22,8 +25,16 @@ pub enum Message {
     RightPanel(RPMsg),
     LeftMenu(LMMsg),
     GridUpdated(Arc<Model<Color>>),
+    HistoryUpdated(Arc<History>),
+    /// view settings of the grid plate, sent to the service to be saved
+    ViewUpdated(ViewState),
+    ViewLoaded(ViewState),
+    CatalogLoaded(Arc<Catalog>),
     Error(String),
     MouseRelease,
+    ModifiersChanged(Modifiers),
+    /// new width and height of the window
+    WindowResized(u32, u32),
 }
 
 impl From<TMMsg> for Message {
@@ -68,10 +79,18 @@ impl From<Message> for TMMsg {
         match msg {
             Message::TopMenu(msg) => msg,
             Message::LeftMenu(LMMsg::Hide) |
-            Message::LeftMenu(LMMsg::ShowResize) => TMMsg::Hide,
+            Message::LeftMenu(LMMsg::ShowResize) |
+            Message::RightPanel(RPMsg::OpenCatalog) |
+            Message::RightPanel(RPMsg::OpenPalette) => TMMsg::Hide,
             Message::LeftPanel(LPMsg::FS(FilesMessage::Open(..))) |
-            Message::LeftPanel(LPMsg::FS(FilesMessage::Save(..))) => TMMsg::Hide,
+            Message::LeftPanel(LPMsg::FS(FilesMessage::OpenCatalog(..))) |
+            Message::LeftPanel(LPMsg::FS(FilesMessage::OpenPalette(..))) |
+            Message::LeftPanel(LPMsg::FS(FilesMessage::Save(..))) |
+            Message::LeftPanel(LPMsg::FS(FilesMessage::Import(..))) => TMMsg::Hide,
             Message::GridUpdated(model) => TMMsg::Palette(PaletteMessage::Updated(model)),
+            Message::Grid(GMsg::Pick(color)) => TMMsg::Palette(PaletteMessage::ActivateColor(color)),
+            // a color dropped outside the palette is not dragged anymore
+            Message::MouseRelease => TMMsg::Palette(PaletteMessage::Release),
             _ => TMMsg::Ignore,
         }
     }
@@ -81,6 +100,7 @@ impl From<Message> for RMMsg {
     fn from(msg: Message) -> Self {
         match msg {
             Message::RightMenu(msg) => msg,
+            Message::CatalogLoaded(_) => RMMsg::ShowCatalog,
             _ => RMMsg::Ignore,
         }
     }
@@ -92,7 +112,10 @@ impl From<Message> for LMMsg {
             Message::LeftMenu(msg) => msg,
             Message::TopMenu(TMMsg::Hide) |
             Message::TopMenu(TMMsg::Open) |
-            Message::TopMenu(TMMsg::Save) => LMMsg::Hide,
+            Message::TopMenu(TMMsg::Save) |
+            Message::TopMenu(TMMsg::Import) |
+            Message::RightPanel(RPMsg::OpenCatalog) |
+            Message::RightPanel(RPMsg::OpenPalette) => LMMsg::Hide,
             _ => LMMsg::Ignore,
         }
     }
@@ -104,10 +127,13 @@ impl From<Message> for GMsg {
         match msg  {
             Grid(msg) => msg,
             GridUpdated(model) => GMsg::GridUpdated(model),
+            ViewLoaded(view) => GMsg::SetView(view),
             LeftMenu(LMMsg::ZoomIn) => GMsg::ZoomIn,
             LeftMenu(LMMsg::ZoomOut) => GMsg::ZoomOut,
             Message::LeftMenu(LMMsg::MoveSeam(moving)) => GMsg::Rotate(-moving),
+            LeftMenu(LMMsg::SetTool(tool)) => GMsg::SetTool(tool),
             MouseRelease => GMsg::MouseRelease,
+            ModifiersChanged(modifiers) => GMsg::SetPicking(modifiers.alt),
             _ => GMsg::Ignore
         }
     }
@@ -120,6 +146,9 @@ impl From<Message> for LPMsg {
             LeftPanel(msg) => msg,
             TopMenu(TMMsg::Open) => LPMsg::ShowOpen,
             TopMenu(TMMsg::Save) => LPMsg::ShowSave,
+            TopMenu(TMMsg::Import) => LPMsg::ShowImport,
+            RightPanel(RPMsg::OpenCatalog) => LPMsg::ShowOpenCatalog,
+            RightPanel(RPMsg::OpenPalette) => LPMsg::ShowOpenPalette,
             TopMenu(TMMsg::Hide) | LeftMenu(LMMsg::Hide) => LPMsg::Hide,
             LeftMenu(LMMsg::ShowResize) => LPMsg::ShowResize,
             GridUpdated(model) => LPMsg::Resize(model.grid().size()),
@@ -135,8 +164,12 @@ impl From<Message> for RPMsg {
             RightPanel(msg) => msg,
             RightMenu(RMMsg::ShowBeads) => RPMsg::ShowBeads,
             RightMenu(RMMsg::ShowColors) => RPMsg::ShowColors,
+            RightMenu(RMMsg::ShowHistory) => RPMsg::ShowHistory,
+            RightMenu(RMMsg::ShowCatalog) => RPMsg::ShowCatalog,
             RightMenu(RMMsg::Hide) => RPMsg::Hide,
             GridUpdated(model) => RPMsg::GridUpdated(model),
+            HistoryUpdated(history) => RPMsg::HistoryUpdated(history),
+            CatalogLoaded(catalog) => RPMsg::CatalogLoaded(catalog),
             _ => RPMsg::Ignore
         }
     }
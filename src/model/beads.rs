@@ -47,7 +47,6 @@ impl<T: Eq + Hash + Clone + Debug> BeadsLine<T> {
     }
 }
 
-
 #[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Bead<T: ColorTrait> {
     pub color: T,
@@ -31,11 +31,20 @@ impl fmt::LowerHex for Color {
 #[derive(Debug)]
 pub enum ParseColorError {
     WrongLen,
-    WrongFirstSymbol,
     Parse(ParseIntError),
     Encoding,
 }
 
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseColorError::WrongLen => write!(f, "A color is #RRGGBB, #RGB or rgb(r, g, b)"),
+            ParseColorError::Parse(e) => write!(f, "Wrong color component: {}", e),
+            ParseColorError::Encoding => write!(f, "Only hex digits are allowed in a color"),
+        }
+    }
+}
+
 impl From<ParseIntError> for ParseColorError {
     fn from(e: ParseIntError) -> Self {
         ParseColorError::Parse(e)
@@ -45,19 +54,38 @@ impl From<ParseIntError> for ParseColorError {
 impl FromStr for Color {
     type Err = ParseColorError;
 
+    /// Accepts `#RRGGBB`, `#RGB`, both without the hash, and `rgb(r, g, b)`
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.len() != 7 {
-            Err(ParseColorError::WrongLen)
-        } else if !s.is_ascii() {
-            Err(ParseColorError::Encoding)
-        } else if !s.starts_with('#') {
-            Err(ParseColorError::WrongFirstSymbol)
-        } else {
-            Ok(Self {
-                r: u8::from_str_radix(&s[1..3], 16)?,
-                g: u8::from_str_radix(&s[3..5], 16)?,
-                b: u8::from_str_radix(&s[5..7], 16)?,
-            })
+        let s = s.trim();
+        if !s.is_ascii() {
+            return Err(ParseColorError::Encoding);
+        }
+        let lowercase = s.to_ascii_lowercase();
+        if let Some(components) = lowercase.strip_prefix("rgb(").and_then(|s|s.strip_suffix(')')) {
+            let components = components.split(',')
+                .map(|component|component.trim().parse::<u8>())
+                .collect::<Result<Vec<_>, _>>()?;
+            return match components[..] {
+                [r, g, b] => Ok(Self {r, g, b}),
+                _ => Err(ParseColorError::WrongLen),
+            };
+        }
+        let hex = s.strip_prefix('#').unwrap_or(s);
+        if !hex.chars().all(|c|c.is_ascii_hexdigit()) {
+            return Err(ParseColorError::Encoding);
+        }
+        match hex.len() {
+            6 => Ok(Self {
+                r: u8::from_str_radix(&hex[0..2], 16)?,
+                g: u8::from_str_radix(&hex[2..4], 16)?,
+                b: u8::from_str_radix(&hex[4..6], 16)?,
+            }),
+            3 => Ok(Self {
+                r: u8::from_str_radix(&hex[0..1], 16)? * 0x11,
+                g: u8::from_str_radix(&hex[1..2], 16)? * 0x11,
+                b: u8::from_str_radix(&hex[2..3], 16)? * 0x11,
+            }),
+            _ => Err(ParseColorError::WrongLen),
         }
     }
 }
@@ -0,0 +1,98 @@
+use super::Color;
+use colors::{Lab, Srgb};
+
+/// Perceptual components of the color: lightness, green–red and blue–yellow axes
+pub fn lab(Color {r, g, b}: Color) -> (f64, f64, f64) {
+    let max = u8::MAX as f32;
+    let lab: Lab = Srgb::new(r as f32 / max, g as f32 / max, b as f32 / max).into();
+    (lab.l as f64, lab.a as f64, lab.b as f64)
+}
+
+/// CIEDE2000 difference of two Lab colors, about 1.0 is the smallest difference the eye notices
+pub fn ciede2000((l1, a1, b1): (f64, f64, f64), (l2, a2, b2): (f64, f64, f64)) -> f64 {
+    let pow7 = |x: f64|x.powi(7);
+    let c_mean = ((a1.hypot(b1)) + (a2.hypot(b2))) / 2.0;
+    let g = 0.5 * (1.0 - (pow7(c_mean) / (pow7(c_mean) + pow7(25.0))).sqrt());
+    let (a1, a2) = (a1 * (1.0 + g), a2 * (1.0 + g));
+    let (c1, c2) = (a1.hypot(b1), a2.hypot(b2));
+    let hue = |a: f64, b: f64| if a == 0.0 && b == 0.0 { 0.0 } else { b.atan2(a).to_degrees().rem_euclid(360.0) };
+    let (h1, h2) = (hue(a1, b1), hue(a2, b2));
+
+    let dl = l2 - l1;
+    let dc = c2 - c1;
+    let dh = if c1 * c2 == 0.0 {
+        0.0
+    } else if (h2 - h1).abs() <= 180.0 {
+        h2 - h1
+    } else if h2 <= h1 {
+        h2 - h1 + 360.0
+    } else {
+        h2 - h1 - 360.0
+    };
+    let dh = 2.0 * (c1 * c2).sqrt() * (dh / 2.0).to_radians().sin();
+
+    let l_mean = (l1 + l2) / 2.0;
+    let c_mean = (c1 + c2) / 2.0;
+    let h_mean = if c1 * c2 == 0.0 {
+        h1 + h2
+    } else if (h1 - h2).abs() <= 180.0 {
+        (h1 + h2) / 2.0
+    } else if h1 + h2 < 360.0 {
+        (h1 + h2 + 360.0) / 2.0
+    } else {
+        (h1 + h2 - 360.0) / 2.0
+    };
+    let t = 1.0
+        - 0.17 * (h_mean - 30.0).to_radians().cos()
+        + 0.24 * (2.0 * h_mean).to_radians().cos()
+        + 0.32 * (3.0 * h_mean + 6.0).to_radians().cos()
+        - 0.20 * (4.0 * h_mean - 63.0).to_radians().cos();
+    let rotation = 30.0 * (-((h_mean - 275.0) / 25.0).powi(2)).exp();
+    let rc = 2.0 * (pow7(c_mean) / (pow7(c_mean) + pow7(25.0))).sqrt();
+    let sl = 1.0 + 0.015 * (l_mean - 50.0).powi(2) / (20.0 + (l_mean - 50.0).powi(2)).sqrt();
+    let sc = 1.0 + 0.045 * c_mean;
+    let sh = 1.0 + 0.015 * c_mean * t;
+    let rt = -(2.0 * rotation).to_radians().sin() * rc;
+
+    let (l, c, h) = (dl / sl, dc / sc, dh / sh);
+    (l * l + c * c + h * h + rt * c * h).sqrt()
+}
+
+/// Index of the perceptually closest candidate and its difference
+pub fn nearest(color: Color, candidates: &[Color]) -> Option<(usize, f64)> {
+    let color = lab(color);
+    candidates.iter()
+        .map(|&candidate|ciede2000(color, lab(candidate)))
+        .enumerate()
+        .min_by(|(_, a), (_, b)|a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn difference() {
+        // reference pairs of Sharma, Wu and Dalal
+        let pairs = [
+            ((50.0, 2.6772, -79.7751), (50.0, 0.0, -82.7485), 2.0425),
+            ((50.0, 2.5, 0.0), (50.0, 0.0, -2.5), 4.3065),
+            ((50.0, 2.5, 0.0), (73.0, 25.0, -18.0), 27.1492),
+            ((60.2574, -34.0099, 36.2677), (60.4626, -34.1751, 39.4387), 1.2644),
+            ((2.0776, 0.0795, -1.1350), (0.9033, -0.0636, -0.5514), 0.9082),
+        ];
+        for &(a, b, expected) in pairs.iter() {
+            assert!((ciede2000(a, b) - expected).abs() < 1e-4, "{:?} {:?}", a, b);
+            assert!((ciede2000(b, a) - expected).abs() < 1e-4);
+        }
+        let (l, _, _) = lab(Color {r: 255, g: 255, b: 255});
+        assert!((l - 100.0).abs() < 0.1);
+        let (l, _, _) = lab(Color {r: 0x77, g: 0x77, b: 0x77});
+        assert!((l - 50.0).abs() < 0.1);
+
+        let candidates = [Color {r: 0, g: 0, b: 0}, Color {r: 200, g: 30, b: 30}, Color {r: 250, g: 250, b: 250}];
+        assert_eq!(nearest(Color {r: 240, g: 10, b: 40}, &candidates).map(|(i, _)|i), Some(1));
+        assert_eq!(nearest(Color {r: 90, g: 90, b: 90}, &candidates).map(|(i, _)|i), Some(0));
+        assert_eq!(nearest(Color::default(), &[]), None);
+    }
+}
@@ -137,6 +137,23 @@ impl <T: Debug + Clone + Default> SimplifiedGrid<T> {
             },
         }
     }
+    pub fn transform(&mut self, transform: Transform) {
+        let width = self.size.width();
+        if matches!(transform, Transform::Mirror | Transform::Turn) {
+            self.data.chunks_mut(width).for_each(|row|row.reverse());
+        }
+        if matches!(transform, Transform::Flip | Transform::Turn) {
+            let mut rows: Vec<_> = self.data.chunks(width).map(|row|row.to_vec()).collect();
+            rows.reverse();
+            self.data = rows.into_iter().flatten().collect();
+        }
+    }
+    /// Rotates a single row the way `rotate` does with all of them
+    pub fn rotate_row(&mut self, row: usize, rotation: isize) {
+        let width = self.size.width();
+        let rotation = rotation.rem_euclid(width as isize) as usize;
+        self.data[row * width..(row + 1) * width].rotate_left(rotation);
+    }
     pub fn rotate(&mut self, rotation: isize) {
         let mut rotation = rotation % (self.size.width() as isize);
         if rotation < 0 {
@@ -210,6 +227,77 @@ impl<T: Debug + Clone> Grid<T> {
             data: self.data.iter().map(|(obj,_)|obj.clone()).collect()
         }
     }
+    /// Copy of the rectangle between two coords, columns wrap through the seam
+    pub fn area(&self, from: Coord, to: Coord) -> SimplifiedGrid<T> {
+        let width = self.width();
+        let last_row = self.size.height() - 1;
+        let top = from.x.min(to.x).min(last_row);
+        let bottom = from.x.max(to.x).min(last_row);
+        let left = from.y.min(to.y);
+        let columns = (from.y.max(to.y) - left + 1).min(width);
+        let data = (top..=bottom)
+            .flat_map(|x| (left..left + columns).map(move |y| self.data[x * width + y % width].0.clone()))
+            .collect();
+        SimplifiedGrid {
+            size: Size {
+                width: NonZeroUsize::new(columns).unwrap(),
+                height: NonZeroUsize::new(bottom - top + 1).unwrap(),
+            },
+            data,
+        }
+    }
+    pub fn get(&self, row: usize, column: usize) -> Option<&T> {
+        if row < self.size.height() && column < self.width() {
+            self.data.get(row * self.width() + column).map(|x|&x.0)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: Debug + Clone> SimplifiedGrid<T> {
+    pub fn map<X: Debug + Clone, F: Fn(&T)->X>(&self, fun: F) -> SimplifiedGrid<X> {
+        SimplifiedGrid {
+            size: self.size,
+            data: self.data.iter().map(fun).collect(),
+        }
+    }
+    /// Items placed with the top left corner at the coord,
+    /// columns wrap through the seam, rows out of the grid are dropped
+    pub fn placed(&self, at: Coord, size: Size) -> Vec<(Coord, T)> {
+        self.data.chunks(self.size.width())
+            .enumerate()
+            .take_while(|(row, _)| at.x + row < size.height())
+            .flat_map(|(row, items)| {
+                items.iter().take(size.width()).enumerate().map(move |(column, item)| {
+                    (Coord {x: at.x + row, y: (at.y + column) % size.width()}, item.clone())
+                })
+            })
+            .collect()
+    }
+}
+
+impl<T: Debug + Clone + PartialEq> Grid<T> {
+    /// Contiguous area of items equal to the item at `start`
+    pub fn region(&self, start: Coord, schema: Schema) -> Result<Vec<Coord>, String> {
+        let item = self.get(start.x, start.y).ok_or("coord out of bounds")?;
+        let width = self.width();
+        let mut visited = vec![false; self.size.capacity()];
+        let mut stack = vec![start];
+        let mut result = Vec::new();
+        visited[start.x * width + start.y] = true;
+        while let Some(coord) = stack.pop() {
+            result.push(coord);
+            for next in schema.neighbours(coord, self.size) {
+                let index = next.x * width + next.y;
+                if !visited[index] && self.data[index].0.eq(item) {
+                    visited[index] = true;
+                    stack.push(next);
+                }
+            }
+        }
+        Ok(result)
+    }
 }
 
 
@@ -223,4 +311,17 @@ impl<T: Debug + Default + Clone> Default for Grid<T> {
 fn negative_remainder() {
     assert_eq!(-3, -3 % 10);
     assert_eq!(-3, -13 %10);
-}
\ No newline at end of file
+}
+
+#[test]
+fn copy_paste_across_seam() {
+    let size = Size {width: NonZeroUsize::new(4).unwrap(), height: NonZeroUsize::new(3).unwrap()};
+    let grid = Grid::frow_raw(size.width, (0..12).map(|i|(i, false)).collect()).unwrap();
+    let area = grid.area(Coord {x: 2, y: 5}, Coord {x: 1, y: 3});
+    assert_eq!(area.size().width(), 3);
+    assert_eq!(area.data, vec![7, 4, 5, 11, 8, 9]);
+    let placed: Vec<_> = area.placed(Coord {x: 2, y: 2}, size).into_iter()
+        .map(|(Coord {x, y}, item)|(x, y, item))
+        .collect();
+    assert_eq!(placed, vec![(2, 2, 7), (2, 3, 4), (2, 0, 5)]);
+}
@@ -11,13 +11,20 @@ mod faces;
 mod model;
 mod line_builder;
 mod palette;
+mod shape;
+pub mod quantize;
+pub mod difference;
+pub mod reduce;
+pub mod symbols;
 
 pub use faces::*;
 pub use grid::Grid;
 pub use model::Model;
 pub use beads::{Bead, BeadsLine};
 pub use color::Color;
-pub use palette::Palette;
+pub use palette::{Palette, ColorInfo};
+pub use shape::Shape;
+pub use symbols::Symbols;
 
 
 pub type ColorBead = Bead<Color>;
@@ -27,7 +34,44 @@ pub type BeadGrid = Grid<Bead<Color>>;
 #[derive(Debug, Copy, Clone)]
 pub enum Side { Top, Left, Right, Bottom }
 
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Transform {
+    /// left and right sides are swapped
+    Mirror,
+    /// top and bottom sides are swapped
+    Flip,
+    /// turn by 180°
+    Turn,
+}
+
+impl Transform {
+    /// Whether odd and even rows swap their offsets when that many rows are transformed
+    pub fn switches_offset(self, rows: usize) -> bool {
+        let odd = rows % 2 == 1;
+        match self {
+            Transform::Mirror => true,
+            Transform::Flip => !odd,
+            Transform::Turn => odd,
+        }
+    }
+}
+
+/// How the grid plate shows the pattern, saved along with it
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ViewState {
+    /// columns the seam is rotated by
+    pub rotation: isize,
+    /// half of the bead size in pixels
+    pub zoom: u16,
+}
+
+impl Default for ViewState {
+    fn default() -> Self {
+        Self { rotation: 0, zoom: 6 }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Schema {
     FirstOffset,
     SecondOffset,
@@ -43,6 +87,47 @@ impl Schema {
             Straight => FirstOffset,
         }
     }
+    /// Height of a bead row relative to the bead width, offset rows nest into each other
+    pub fn row_height(self) -> f32 {
+        match self {
+            Schema::Straight => 1.0,
+            Schema::FirstOffset | Schema::SecondOffset => 3f32.sqrt() / 2.0,
+        }
+    }
+    /// Schema with the opposite offset of odd and even rows
+    pub fn mirror(self) -> Self {
+        use Schema::*;
+        match self {
+            FirstOffset => SecondOffset,
+            SecondOffset => FirstOffset,
+            Straight => Straight,
+        }
+    }
+    /// Whether the row is shown half a bead to the right of its neighbour rows
+    pub fn shifted(self, row: usize) -> bool {
+        matches!((self, row % 2), (Schema::FirstOffset, 0) | (Schema::SecondOffset, 1))
+    }
+    /// Beads touching the given one, as they are shown on the grid plate.
+    /// Left and right edges are adjacent (the rope is a tube), offset schemas
+    /// touch two beads in each of the neighbour rows.
+    pub fn neighbours(self, Coord {x, y}: Coord, size: Size) -> Vec<Coord> {
+        let width = size.width();
+        let left = (y + width - 1) % width;
+        let right = (y + 1) % width;
+        let columns = match (self, x % 2) {
+            (Schema::Straight, _) => vec![y],
+            (Schema::FirstOffset, 0) | (Schema::SecondOffset, 1) => vec![y, right],
+            _ => vec![left, y],
+        };
+        let mut result = vec![Coord {x, y: left}, Coord {x, y: right}];
+        if x > 0 {
+            result.extend(columns.iter().map(|&y|Coord {x: x - 1, y}));
+        }
+        if x + 1 < size.height() {
+            result.extend(columns.iter().map(|&y|Coord {x: x + 1, y}));
+        }
+        result
+    }
 }
 
 impl Default for Schema {
@@ -51,7 +136,7 @@ impl Default for Schema {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Size {
     pub width: NonZeroUsize,
     pub height: NonZeroUsize,
@@ -78,7 +163,7 @@ impl Size {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct Coord {
     pub x: usize,
     pub y: usize,
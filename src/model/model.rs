@@ -1,6 +1,7 @@
 use super::*;
 use super::grid::SimplifiedGrid;
 use super::line_builder::BeadsLineBuilder;
+use fxhash::FxHashMap;
 
 impl<T: ColorTrait> Default for Model<T> {
     fn default() -> Self {
@@ -14,14 +15,15 @@ pub struct Model<T: ColorTrait> {
     palette: Palette<T>,
     grid: Grid<Bead<T>>,
     line: BeadsLine<Bead<T>>,
+    symbols: Symbols<T>,
 }
 
+/// Colors of the line in the order they are met
 fn create_palette<T: ColorTrait>(line: &BeadsLine<Bead<T>>) -> Palette<T> {
-    line.map(|Bead{color, ..}|{color.clone()})
-        .summary()
-        .keys()
-        .fold(Palette::new(),|mut palette, item| {
-            palette.add_color(item.clone());
+    line.line()
+        .iter()
+        .fold(Palette::new(),|mut palette, (Bead {color, ..}, _)| {
+            palette.add_color(color.clone());
             palette
         })
 }
@@ -30,7 +32,9 @@ impl<T: ColorTrait> From<BeadsLine<Bead<T>>> for Model<T> {
     fn from(line: BeadsLine<Bead<T>>) -> Self {
         let grid = line.grid();
         let palette = create_palette(&line);
-        Model {palette, grid, line}
+        let mut model = Model {palette, grid, line, symbols: Symbols::new()};
+        model.assign_symbols();
+        model
     }
 }
 
@@ -40,7 +44,9 @@ impl <T: ColorTrait> From<Grid<Bead<T>>> for Model<T> {
         let line = builder.build(grid.as_table_iter(), grid.size().width);
         let grid = line.grid();
         let palette = create_palette(&line);
-        Model {palette, line, grid}
+        let mut model = Model {palette, line, grid, symbols: Symbols::new()};
+        model.assign_symbols();
+        model
     }
 }
 
@@ -54,18 +60,54 @@ impl<T: ColorTrait> Model<T> {
     pub fn line(&self) -> &BeadsLine<Bead<T>> {
         &self.line
     }
+    pub fn palette(&self) -> &Palette<T> {
+        &self.palette
+    }
     pub fn schema(&self) -> Schema {
         self.line.schema
     }
+    pub fn symbols(&self) -> &Symbols<T> {
+        &self.symbols
+    }
+    pub fn legend(&self) -> Vec<(T, usize, &str)> {
+        self.symbols.legend(&self.line)
+    }
+    /// Returns the previous symbol of the color
+    pub fn set_symbol(&mut self, color: T, symbol: String) -> Result<Option<String>, String> {
+        self.symbols.set(color, symbol)
+    }
+    /// Replaces the symbols, colors missing in them get generated ones. Returns the previous symbols
+    pub fn set_symbols(&mut self, symbols: Symbols<T>) -> Symbols<T> {
+        let prev = core::mem::replace(&mut self.symbols, symbols);
+        self.assign_symbols();
+        prev
+    }
+    fn assign_symbols(&mut self) {
+        let colors: Vec<_> = self.line.line().iter().map(|(bead, _)|bead.color.clone()).collect();
+        self.symbols.assign(colors);
+    }
     pub fn add_color(&mut self, color: T) {
         self.palette.add_color(color);
     }
+    pub fn insert_color(&mut self, index: usize, color: T) {
+        self.palette.insert_color(index, color);
+    }
+    pub fn set_color_info(&mut self, color: T, info: ColorInfo) {
+        self.palette.set_info(color, info);
+    }
+    pub fn move_color(&mut self, from: usize, to: usize) -> Result<(), String> {
+        self.palette.move_color(from, to)
+    }
     pub fn activate_color(&mut self, color: T) -> T {
         self.palette.activate(color)
     }
     pub fn remove_color(&mut self) {
         self.palette.remove_color();
     }
+    /// Replaces the palette, returns the previous one
+    pub fn set_palette(&mut self, palette: Palette<T>) -> Palette<T> {
+        core::mem::replace(&mut self.palette, palette)
+    }
     pub fn set_schema(&mut self, schema: Schema) {
         self.line.schema = schema;
         self.unfill_grid();
@@ -78,6 +120,7 @@ impl<T: ColorTrait> Model<T> {
     fn update_line(&mut self) {
         let builder: BeadsLineBuilder = self.line.schema.into();
         self.line = builder.build(self.grid.as_table_iter(), self.grid.size().width);
+        self.assign_symbols();
     }
     fn simplified_grid(&mut self) -> SimplifiedGrid<Bead<T>> {
         self.unfill_grid();
@@ -98,6 +141,88 @@ impl<T: ColorTrait> Model<T> {
             Ok(Some(bead))
         }
     }
+    pub fn fill(&mut self, row: usize, column: usize) -> Result<Vec<(Coord, T)>, String> {
+        let color = self.palette.activated().clone();
+        let region = self.grid
+            .map(|Bead {color, ..}|color.clone())
+            .region(Coord {x: row, y: column}, self.schema())?;
+        self.paint(region.into_iter().map(|coord|(coord, color.clone())).collect())
+    }
+    /// Draws the shape with the activated color, columns beyond the width are wrapped through the seam
+    pub fn draw_shape(&mut self, shape: Shape, from: Coord, to: Coord) -> Result<Vec<(Coord, T)>, String> {
+        let color = self.palette.activated().clone();
+        let width = self.size().width();
+        let items = shape.coords(from, to).into_iter()
+            .map(|Coord {x, y}|(Coord {x, y: y % width}, color.clone()))
+            .collect();
+        self.paint(items)
+    }
+    /// Sets colors of many beads at once, returns previous colors of changed beads
+    pub fn paint(&mut self, items: Vec<(Coord, T)>) -> Result<Vec<(Coord, T)>, String> {
+        let size = self.size();
+        if items.iter().any(|(Coord {x, y}, _)| *x >= size.height() || *y >= size.width()) {
+            return Err("coord out of bounds".to_string());
+        }
+        let mut unfill = false;
+        let mut prev = Vec::new();
+        for (coord, color) in items {
+            let bead = self.grid.get_mut(coord.x, coord.y)?;
+            if !color.eq(&bead.color) {
+                unfill |= bead.filled;
+                let Bead {color, ..} = core::mem::replace(bead, Bead {color, filled: false});
+                prev.push((coord, color));
+            }
+        }
+        prev.reverse();
+        if !prev.is_empty() {
+            if unfill {
+                self.unfill_grid();
+            }
+            self.update_line();
+        }
+        Ok(prev)
+    }
+    /// Sets colors of many beads at once keeping their filled flags, so the strung items stay marked
+    /// unless they merge with others. Returns previous colors of changed beads
+    pub fn recolor(&mut self, items: Vec<(Coord, T)>) -> Result<Vec<(Coord, T)>, String> {
+        let size = self.size();
+        if items.iter().any(|(Coord {x, y}, _)| *x >= size.height() || *y >= size.width()) {
+            return Err("coord out of bounds".to_string());
+        }
+        let mut prev = Vec::new();
+        for (coord, color) in items {
+            let bead = self.grid.get_mut(coord.x, coord.y)?;
+            if !color.eq(&bead.color) {
+                prev.push((coord, core::mem::replace(&mut bead.color, color)));
+            }
+        }
+        prev.reverse();
+        if !prev.is_empty() {
+            self.update_line();
+        }
+        Ok(prev)
+    }
+    /// Beads of the mapped colors with their new colors
+    fn mapped(&self, mapping: &FxHashMap<T, T>) -> Vec<(Coord, T)> {
+        self.grid.as_table_iter()
+            .enumerate()
+            .flat_map(|(x, row)|row.enumerate().filter_map(move |(y, Bead {color, ..})|{
+                mapping.get(color).map(|color|(Coord {x, y}, color.clone()))
+            }))
+            .collect()
+    }
+    /// Replaces the colors everywhere at once, in the beads and in the palette.
+    /// Returns previous colors of changed beads
+    pub fn replace_colors(&mut self, mapping: &FxHashMap<T, T>) -> Result<Vec<(Coord, T)>, String> {
+        let prev = self.recolor(self.mapped(mapping))?;
+        self.palette.replace_colors(mapping);
+        Ok(prev)
+    }
+    /// Swaps the colors of the beads, the palette is kept. Returns previous colors of changed beads
+    pub fn swap_colors(&mut self, first: T, second: T) -> Result<Vec<(Coord, T)>, String> {
+        let mapping = vec![(first.clone(), second.clone()), (second, first)].into_iter().collect();
+        self.recolor(self.mapped(&mapping))
+    }
     pub fn toggle_filled(&mut self, index: usize) -> Result<bool, String> {
         let obj = self.line.get_mut(index).ok_or("Toggle is out of bounds")?;
         let filled = obj.filled;
@@ -124,8 +249,36 @@ impl<T: ColorTrait> Model<T> {
             grid.size().width
         );
         self.grid = self.line.grid();
+        self.assign_symbols();
     }
 
+    /// Transforms the whole pattern. Offset schema is switched when odd and even rows
+    /// change places, so beads keep touching the same neighbours
+    pub fn transform(&mut self, transform: Transform) {
+        let mut grid = self.simplified_grid();
+        grid.transform(transform);
+        if transform.switches_offset(grid.size().height()) {
+            self.line.schema = self.line.schema.mirror();
+        }
+        self.update_from_simplified(grid);
+    }
+    /// Transforms beads inside the rectangle, columns wrap through the seam.
+    /// The schema stays, so when odd and even rows swap their offsets the shifted rows
+    /// move a column to the left, the beads wrapping inside the rectangle
+    pub fn transform_area(&mut self, transform: Transform, from: Coord, to: Coord) -> Result<Vec<(Coord, T)>, String> {
+        let mut area = self.grid.area(from, to).map(|Bead {color, ..}|color.clone());
+        area.transform(transform);
+        let corner = Coord {x: from.x.min(to.x), y: from.y.min(to.y)};
+        let schema = self.schema();
+        if schema != Schema::Straight && transform.switches_offset(area.size().height()) {
+            for row in 0..area.size().height() {
+                if schema.shifted(corner.x + row) {
+                    area.rotate_row(row, 1);
+                }
+            }
+        }
+        self.paint(area.placed(corner, self.size()))
+    }
     pub fn rotate(&mut self, rotation: isize) {
         let mut grid = self.simplified_grid();
         grid.rotate(rotation);
@@ -159,6 +312,12 @@ impl<T: ColorTrait> AsRef<Palette<T>> for Model<T> {
     }
 }
 
+impl<T: ColorTrait> AsRef<Symbols<T>> for Model<T> {
+    fn as_ref(&self) -> &Symbols<T> {
+        &self.symbols
+    }
+}
+
 impl<T: ColorTrait> GetSchema for Model<T> {
     fn get_schema(&self) -> Schema {
         self.line.schema
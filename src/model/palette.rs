@@ -1,39 +1,179 @@
 use super::*;
 use fxhash::FxHashMap;
 
+/// What is known about the beads of a palette color, any part may be empty
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ColorInfo {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub manufacturer: String,
+    /// catalog number of the manufacturer
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub code: String,
+    /// matte, transparent, metallic and so on
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub finish: String,
+}
+
+impl ColorInfo {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+    /// Manufacturer, code and name followed by the finish in parentheses: "Miyuki DB-10 Black (opaque)"
+    pub fn label(&self) -> String {
+        let mut label = [&self.manufacturer, &self.code, &self.name].iter()
+            .filter(|part|!part.is_empty())
+            .map(|part|part.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+        if !self.finish.is_empty() {
+            if !label.is_empty() {
+                label.push(' ');
+            }
+            label += &format!("({})", self.finish);
+        }
+        label
+    }
+}
+
+/// Colors in the order chosen by the user, one of them is activated
 #[derive(Debug, Clone)]
 pub struct Palette<T: ColorTrait> {
-    colors: FxHashMap<T, bool>,
+    colors: Vec<T>,
     activated: T,
+    info: FxHashMap<T, ColorInfo>,
 }
 
 impl<T: ColorTrait> Palette<T> {
     pub fn new() -> Self {
-        let mut colors = FxHashMap::default();
-        colors.insert(T::default(), true);
-        Self {colors, activated: T::default()}
+        Self {colors: vec![T::default()], activated: T::default(), info: FxHashMap::default()}
+    }
+    pub fn info(&self, color: &T) -> Option<&ColorInfo> {
+        self.info.get(color)
+    }
+    /// Empty info is removed
+    pub fn set_info(&mut self, color: T, info: ColorInfo) {
+        if info.is_empty() {
+            self.info.remove(&color);
+        } else {
+            self.info.insert(color, info);
+        }
     }
     pub fn activated(&self) -> &T {
         &self.activated
     }
-    pub fn colors(&self) -> &FxHashMap<T, bool> {
+    pub fn colors(&self) -> &[T] {
         &self.colors
     }
+    pub fn contains(&self, color: &T) -> bool {
+        self.colors.contains(color)
+    }
+    pub fn position(&self, color: &T) -> Option<usize> {
+        self.colors.iter().position(|item|item == color)
+    }
+    /// Appends the color if it is missing
     pub fn add_color(&mut self, color: T) {
-        self.colors.insert(color, false);
+        if !self.contains(&color) {
+            self.colors.push(color);
+        }
+    }
+    /// Puts the color at the index, the color already in the palette is moved there
+    pub fn insert_color(&mut self, index: usize, color: T) {
+        if let Some(position) = self.position(&color) {
+            self.colors.remove(position);
+        }
+        let index = index.min(self.colors.len());
+        self.colors.insert(index, color);
+    }
+    pub fn move_color(&mut self, from: usize, to: usize) -> Result<(), String> {
+        if from >= self.colors.len() || to >= self.colors.len() {
+            return Err("Palette color is out of bounds".to_string());
+        }
+        let color = self.colors.remove(from);
+        self.colors.insert(to, color);
+        Ok(())
+    }
+    /// Replaces all the colors at once, so they may be swapped. Colors keep their places,
+    /// the activation follows them, info of the replaced colors is dropped
+    pub fn replace_colors(&mut self, mapping: &FxHashMap<T, T>) {
+        let colors = core::mem::take(&mut self.colors);
+        colors.into_iter().for_each(|color|self.add_color(mapping.get(&color).cloned().unwrap_or(color)));
+        mapping.iter()
+            .filter(|(from, to)|from != to)
+            .for_each(|(from, _)|{ self.info.remove(from); });
+        if let Some(activated) = mapping.get(&self.activated) {
+            self.activated = activated.clone();
+        }
+        self.add_color(self.activated.clone());
     }
     pub fn activate(&mut self, color: T) -> T {
-        let prev = self.activated.clone();
-        self.colors.insert(prev.clone(), false);
-        self.colors.insert(color.clone(), true);
-        self.activated = color;
-        return prev
+        self.add_color(color.clone());
+        core::mem::replace(&mut self.activated, color)
     }
     pub fn remove_color(&mut self) {
         if self.colors.len() > 1 {
-            self.colors.remove(&self.activated);
-            self.colors.insert(T::default(), true);
+            if let Some(position) = self.position(&self.activated) {
+                self.colors.remove(position);
+            }
+            self.info.remove(&self.activated);
+            self.add_color(T::default());
             self.activated = T::default();
         }
     }
 }
+
+impl<T: ColorTrait> From<Vec<T>> for Palette<T> {
+    /// The first color is activated, the empty list gives the default palette
+    fn from(colors: Vec<T>) -> Self {
+        let mut palette = Self::new();
+        if let Some(first) = colors.first() {
+            palette.activated = first.clone();
+            palette.colors.clear();
+            colors.into_iter().for_each(|color|palette.add_color(color));
+        }
+        palette
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn order() {
+        let mut palette = Palette::from(vec![3, 1, 2, 1]);
+        assert_eq!(palette.colors(), &[3, 1, 2]);
+        assert_eq!(palette.activated(), &3);
+        palette.add_color(0);
+        palette.insert_color(1, 5);
+        assert_eq!(palette.colors(), &[3, 5, 1, 2, 0]);
+        palette.insert_color(0, 2);
+        palette.move_color(1, 4).unwrap();
+        assert_eq!(palette.colors(), &[2, 5, 1, 0, 3]);
+        assert!(palette.move_color(5, 0).is_err());
+        palette.activate(1);
+        palette.remove_color();
+        assert_eq!(palette.colors(), &[2, 5, 0, 3]);
+        assert_eq!(palette.activated(), &0);
+        let info = ColorInfo {
+            name: "Black".to_string(),
+            manufacturer: "Miyuki".to_string(),
+            code: "DB-10".to_string(),
+            finish: "opaque".to_string(),
+        };
+        assert_eq!(info.label(), "Miyuki DB-10 Black (opaque)");
+        assert_eq!(ColorInfo {finish: "matte".to_string(), ..Default::default()}.label(), "(matte)");
+        palette.set_info(0, info.clone());
+        assert_eq!(palette.info(&0), Some(&info));
+        palette.remove_color();
+        assert_eq!(palette.info(&0), None);
+
+        palette.set_info(5, ColorInfo {name: "Five".to_string(), ..Default::default()});
+        palette.activate(5);
+        palette.replace_colors(&vec![(5, 2), (2, 5), (3, 7), (0, 7)].into_iter().collect());
+        assert_eq!(palette.colors(), &[5, 2, 7]);
+        assert_eq!(palette.activated(), &2);
+        assert_eq!(palette.info(&5), None);
+    }
+}
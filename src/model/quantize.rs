@@ -0,0 +1,94 @@
+use super::Color;
+
+fn channels(Color {r, g, b}: Color) -> [u8; 3] {
+    [r, g, b]
+}
+
+fn distance(a: Color, b: Color) -> u32 {
+    channels(a).iter().zip(channels(b).iter())
+        .map(|(&a, &b)|(a as i32 - b as i32).pow(2) as u32)
+        .sum()
+}
+
+/// The color of the palette closest to the given one
+pub fn nearest(color: Color, palette: &[Color]) -> Color {
+    palette.iter()
+        .copied()
+        .min_by_key(|&item|distance(color, item))
+        .unwrap_or(color)
+}
+
+fn average(colors: &[Color]) -> Color {
+    let mut sum = [0usize; 3];
+    for &color in colors {
+        for (sum, channel) in sum.iter_mut().zip(channels(color).iter()) {
+            *sum += *channel as usize;
+        }
+    }
+    let len = colors.len().max(1);
+    Color {
+        r: (sum[0] / len) as u8,
+        g: (sum[1] / len) as u8,
+        b: (sum[2] / len) as u8,
+    }
+}
+
+/// Widest channel of the colors and its range
+fn widest(colors: &[Color]) -> (usize, u8) {
+    (0..3).map(|channel| {
+        let values = colors.iter().map(|&color|channels(color)[channel]);
+        let range = values.clone().max().unwrap_or(0) - values.min().unwrap_or(0);
+        (channel, range)
+    })
+        .max_by_key(|&(_, range)|range)
+        .unwrap_or((0, 0))
+}
+
+/// Median cut palette of at most `count` colors, the colors are weighted by repetition
+pub fn median_cut(colors: &[Color], count: usize) -> Vec<Color> {
+    if colors.is_empty() || count == 0 {
+        return Vec::new();
+    }
+    let mut boxes = vec![colors.to_vec()];
+    while boxes.len() < count {
+        let (index, channel, range) = boxes.iter()
+            .enumerate()
+            .map(|(i, colors)|{
+                let (channel, range) = widest(colors);
+                (i, channel, range)
+            })
+            .max_by_key(|&(_, _, range)|range)
+            .unwrap();
+        if range == 0 {
+            break;
+        }
+        let mut colors = boxes.swap_remove(index);
+        colors.sort_unstable_by_key(|&color|channels(color)[channel]);
+        let upper = colors.split_off(colors.len() / 2);
+        boxes.push(colors);
+        boxes.push(upper);
+    }
+    let mut result: Vec<_> = boxes.iter().map(|colors|average(colors)).collect();
+    result.sort_unstable_by_key(|&color|channels(color));
+    result.dedup();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quantize() {
+        let red = Color {r: 250, g: 0, b: 0};
+        let dark_red = Color {r: 240, g: 10, b: 0};
+        let blue = Color {r: 0, g: 0, b: 250};
+        let colors = vec![red, dark_red, blue, blue];
+        let palette = median_cut(&colors, 2);
+        assert_eq!(palette.len(), 2);
+        assert_eq!(nearest(blue, &palette), blue);
+        assert_eq!(nearest(red, &palette), nearest(dark_red, &palette));
+        assert_eq!(median_cut(&colors, 10).len(), 3);
+        assert_eq!(median_cut(&[red, red], 4), vec![red]);
+    }
+}
@@ -0,0 +1,149 @@
+use super::{Bead, BeadsLine, Color};
+use super::difference::{lab, ciede2000};
+use fxhash::FxHashMap;
+
+/// How the colors of a pattern are merged
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Reduction {
+    /// at most that many colors are left, groups are found by median cut in Lab space
+    Count(usize),
+    /// colors closer than the CIEDE2000 difference are merged
+    Threshold(f64),
+}
+
+type Lab = (f64, f64, f64);
+
+fn component((l, a, b): Lab, index: usize) -> f64 {
+    [l, a, b][index]
+}
+
+/// Widest Lab component of the group and its range
+fn widest(group: &[(Color, Lab, usize)]) -> (usize, f64) {
+    (0..3).map(|index| {
+        let values = group.iter().map(|(_, lab, _)|component(*lab, index));
+        let min = values.clone().fold(f64::INFINITY, f64::min);
+        let max = values.fold(f64::NEG_INFINITY, f64::max);
+        (index, max - min)
+    })
+        .max_by(|(_, a), (_, b)|a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+        .unwrap_or((0, 0.0))
+}
+
+/// Groups split at the bead weighted median of the widest component
+fn median_cut(colors: Vec<(Color, Lab, usize)>, count: usize) -> Vec<Vec<(Color, Lab, usize)>> {
+    let mut groups = vec![colors];
+    while groups.len() < count.max(1) {
+        let widest = groups.iter()
+            .enumerate()
+            .filter(|(_, group)|group.len() > 1)
+            .map(|(i, group)|(i, widest(group)))
+            .max_by(|(_, (_, a)), (_, (_, b))|a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        let (index, (channel, _)) = match widest {
+            Some(widest) => widest,
+            None => break,
+        };
+        let mut group = groups.swap_remove(index);
+        group.sort_by(|(_, a, _), (_, b, _)|{
+            component(*a, channel).partial_cmp(&component(*b, channel)).unwrap_or(std::cmp::Ordering::Equal)
+        });
+        let total: usize = group.iter().map(|(_, _, count)|count).sum();
+        let mut weight = 0;
+        let median = group.iter()
+            .position(|(_, _, count)|{
+                weight += count;
+                weight * 2 >= total
+            })
+            .unwrap_or(0);
+        let upper = group.split_off((median + 1).min(group.len() - 1));
+        groups.push(group);
+        groups.push(upper);
+    }
+    groups
+}
+
+/// Maps the colors to be merged to the colors they are merged into.
+/// Colors are given with their bead counts, the most used color of a group stays,
+/// so no new colors appear in the pattern
+pub fn reduce(colors: &[(Color, usize)], reduction: Reduction) -> FxHashMap<Color, Color> {
+    let mut colors: Vec<_> = colors.iter().map(|&(color, count)|(color, lab(color), count)).collect();
+    // most used first, the order of equally used ones does not depend on the input
+    colors.sort_by_key(|&(Color {r, g, b}, _, count)|(std::cmp::Reverse(count), r, g, b));
+    let mut mapping = FxHashMap::default();
+    match reduction {
+        Reduction::Count(count) => {
+            for group in median_cut(colors, count) {
+                let kept = match group.iter()
+                    .max_by_key(|&&(Color {r, g, b}, _, count)|(count, std::cmp::Reverse((r, g, b)))) {
+                    Some(&(kept, _, _)) => kept,
+                    None => continue,
+                };
+                group.into_iter()
+                    .filter(|(color, _, _)|*color != kept)
+                    .for_each(|(color, _, _)|{ mapping.insert(color, kept); });
+            }
+        },
+        Reduction::Threshold(threshold) => {
+            let mut kept: Vec<(Color, Lab)> = Vec::new();
+            for (color, lab, _) in colors {
+                let closest = kept.iter()
+                    .map(|&(kept, kept_lab)|(kept, ciede2000(lab, kept_lab)))
+                    .filter(|&(_, difference)|difference <= threshold)
+                    .min_by(|(_, a), (_, b)|a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+                match closest {
+                    Some((kept, _)) => { mapping.insert(color, kept); },
+                    None => kept.push((color, lab)),
+                }
+            }
+        },
+    }
+    mapping
+}
+
+/// Reduction of the line colors and count of the beads it changes
+pub fn reduce_line(line: &BeadsLine<Bead<Color>>, reduction: Reduction) -> (FxHashMap<Color, Color>, usize) {
+    let counts: Vec<_> = line.map(|bead|bead.color).summary().into_iter().collect();
+    let mapping = reduce(&counts, reduction);
+    let changed = counts.iter()
+        .filter(|(color, _)|mapping.contains_key(color))
+        .map(|(_, count)|count)
+        .sum();
+    (mapping, changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Model;
+
+    #[test]
+    fn reduction() {
+        let red = Color {r: 250, g: 0, b: 0};
+        let dark_red = Color {r: 245, g: 5, b: 0};
+        let blue = Color {r: 0, g: 0, b: 250};
+        let light_blue = Color {r: 5, g: 5, b: 250};
+        let white = Color::default();
+        let colors = [(red, 1), (dark_red, 5), (blue, 3), (light_blue, 2), (white, 10)];
+
+        let mapping = reduce(&colors, Reduction::Count(3));
+        assert_eq!(mapping.len(), 2);
+        assert_eq!(mapping.get(&red), Some(&dark_red));
+        assert_eq!(mapping.get(&light_blue), Some(&blue));
+        assert_eq!(reduce(&colors, Reduction::Count(1)).len(), 4);
+        assert!(reduce(&colors, Reduction::Count(10)).is_empty());
+
+        let mapping = reduce(&colors, Reduction::Threshold(5.0));
+        assert_eq!(mapping, reduce(&colors, Reduction::Count(3)));
+        assert!(reduce(&colors, Reduction::Threshold(0.1)).is_empty());
+        assert!(reduce(&[], Reduction::Count(2)).is_empty());
+
+        let mut model = Model::default();
+        model.activate_color(red);
+        model.set(0, 0).unwrap();
+        model.activate_color(dark_red);
+        model.set(0, 1).unwrap();
+        model.set(0, 2).unwrap();
+        let (mapping, changed) = reduce_line(model.line(), Reduction::Count(2));
+        assert_eq!(mapping.get(&red), Some(&dark_red));
+        assert_eq!(changed, 1);
+    }
+}
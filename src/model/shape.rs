@@ -0,0 +1,120 @@
+use super::*;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Shape {
+    Line,
+    Rectangle,
+    FilledRectangle,
+    Ellipse,
+}
+
+impl Shape {
+    /// Coords of the shape between two points. Columns are not wrapped here,
+    /// so callers may pass columns beyond the grid width to cross the seam.
+    pub fn coords(self, from: Coord, to: Coord) -> Vec<Coord> {
+        let (top, bottom) = (from.x.min(to.x), from.x.max(to.x));
+        let (left, right) = (from.y.min(to.y), from.y.max(to.y));
+        let mut result = match self {
+            Shape::Line => line(from, to),
+            Shape::Rectangle => {
+                let mut result = Vec::new();
+                for y in left..=right {
+                    result.push(Coord {x: top, y});
+                    result.push(Coord {x: bottom, y});
+                }
+                for x in top..=bottom {
+                    result.push(Coord {x, y: left});
+                    result.push(Coord {x, y: right});
+                }
+                result
+            },
+            Shape::FilledRectangle => (top..=bottom)
+                .flat_map(|x|(left..=right).map(move |y|Coord {x, y}))
+                .collect(),
+            Shape::Ellipse => ellipse(top, left, bottom, right),
+        };
+        result.sort_unstable_by_key(|&Coord {x, y}|(x, y));
+        result.dedup();
+        result
+    }
+}
+
+fn line(from: Coord, to: Coord) -> Vec<Coord> {
+    // same beads regardless of the drawing direction
+    let (from, to) = if (from.x, from.y) <= (to.x, to.y) { (from, to) } else { (to, from) };
+    let (mut x, mut y) = (from.x as isize, from.y as isize);
+    let (x1, y1) = (to.x as isize, to.y as isize);
+    let dx = (x1 - x).abs();
+    let dy = -(y1 - y).abs();
+    let sx = if x < x1 { 1 } else { -1 };
+    let sy = if y < y1 { 1 } else { -1 };
+    let mut err = dx + dy;
+    let mut result = Vec::new();
+    loop {
+        result.push(Coord {x: x as usize, y: y as usize});
+        if x == x1 && y == y1 {
+            break;
+        }
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y += sy;
+        }
+    }
+    result
+}
+
+fn ellipse(top: usize, left: usize, bottom: usize, right: usize) -> Vec<Coord> {
+    let cx = (top + bottom) as f64 / 2.0;
+    let cy = (left + right) as f64 / 2.0;
+    let a = (bottom - top) as f64 / 2.0;
+    let b = (right - left) as f64 / 2.0;
+    // distance from center to the border along the other axis
+    let extent = |offset: f64, radius: f64, other: f64| {
+        if radius == 0.0 {
+            other
+        } else {
+            other * (1.0 - (offset / radius).powi(2)).max(0.0).sqrt()
+        }
+    };
+    let mut result = Vec::new();
+    for x in top..=bottom {
+        let d = extent(x as f64 - cx, a, b);
+        result.push(Coord {x, y: (cy - d).round() as usize});
+        result.push(Coord {x, y: (cy + d).round() as usize});
+    }
+    for y in left..=right {
+        let d = extent(y as f64 - cy, b, a);
+        result.push(Coord {x: (cx - d).round() as usize, y});
+        result.push(Coord {x: (cx + d).round() as usize, y});
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coords(v: &[(usize, usize)]) -> Vec<Coord> {
+        v.iter().map(|&(x, y)|Coord {x, y}).collect()
+    }
+
+    #[test]
+    fn shapes() {
+        let from = Coord {x: 0, y: 0};
+        let to = Coord {x: 2, y: 4};
+        assert_eq!(Shape::Line.coords(from, to), coords(&[(0, 0), (1, 1), (1, 2), (2, 3), (2, 4)]));
+        assert_eq!(Shape::Line.coords(to, from), Shape::Line.coords(from, to));
+        assert_eq!(Shape::Rectangle.coords(from, to).len(), 12);
+        assert_eq!(Shape::FilledRectangle.coords(from, to).len(), 15);
+        assert_eq!(Shape::Ellipse.coords(from, to), coords(&[
+            (0, 1), (0, 2), (0, 3),
+            (1, 0), (1, 4),
+            (2, 1), (2, 2), (2, 3),
+        ]));
+    }
+}
@@ -0,0 +1,120 @@
+use super::*;
+use fxhash::{FxHashMap, FxHashSet};
+
+/// Symbol of the colors which have none
+pub const UNDEFINED_SYMBOL: &str = "?";
+
+/// Letters marking the colors in the bead list and on prints.
+/// A color keeps its symbol after it disappears from the pattern, so it gets the same one back
+#[derive(Debug, Clone)]
+pub struct Symbols<T: ColorTrait> {
+    assigned: FxHashMap<T, String>,
+}
+
+/// The symbol generated for the index: A…Z, AA…ZZ, AAA…
+pub fn generated(index: usize) -> String {
+    let mut index = index;
+    let mut symbol = Vec::new();
+    loop {
+        symbol.push(b'A' + (index % 26) as u8);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    symbol.reverse();
+    String::from_utf8(symbol).expect("symbols are ASCII letters")
+}
+
+/// Generated symbols are ordered by length first, so B comes before AA
+fn order(symbol: &str) -> (usize, &str) {
+    (symbol.chars().count(), symbol)
+}
+
+impl<T: ColorTrait> Symbols<T> {
+    pub fn new() -> Self {
+        Self {assigned: FxHashMap::default()}
+    }
+    pub fn get(&self, color: &T) -> &str {
+        self.assigned.get(color).map(String::as_str).unwrap_or(UNDEFINED_SYMBOL)
+    }
+    pub fn assigned(&self) -> &FxHashMap<T, String> {
+        &self.assigned
+    }
+    /// Gives the first unused generated symbols to the colors without one, in the given order
+    pub fn assign<I: IntoIterator<Item=T>>(&mut self, colors: I) {
+        let mut used: FxHashSet<String> = self.assigned.values().cloned().collect();
+        let mut next = 0;
+        for color in colors {
+            if self.assigned.contains_key(&color) {
+                continue;
+            }
+            let symbol = loop {
+                let symbol = generated(next);
+                next += 1;
+                if !used.contains(&symbol) {
+                    break symbol;
+                }
+            };
+            used.insert(symbol.clone());
+            self.assigned.insert(color, symbol);
+        }
+    }
+    /// Colors of the line with their total counts and symbols, ordered by the symbols
+    pub fn legend(&self, line: &BeadsLine<Bead<T>>) -> Vec<(T, usize, &str)> {
+        let mut legend: Vec<_> = line.map(|bead|bead.color.clone())
+            .summary()
+            .into_iter()
+            .map(|(color, count)|{
+                let symbol = self.get(&color);
+                (color, count, symbol)
+            })
+            .collect();
+        legend.sort_by(|(_, _, a), (_, _, b)|order(a).cmp(&order(b)));
+        legend
+    }
+    /// Chosen by the user, returns the previous symbol of the color
+    pub fn set(&mut self, color: T, symbol: String) -> Result<Option<String>, String> {
+        let symbol = symbol.trim().to_string();
+        if symbol.is_empty() || symbol.chars().count() > 3 {
+            return Err("A symbol is one to three characters long".to_string());
+        }
+        if self.assigned.iter().any(|(item, used)|used == &symbol && item != &color) {
+            return Err(format!("Symbol \"{}\" is used by another color", symbol));
+        }
+        Ok(self.assigned.insert(color, symbol))
+    }
+}
+
+impl<T: ColorTrait> std::iter::FromIterator<(T, String)> for Symbols<T> {
+    fn from_iter<I: IntoIterator<Item=(T, String)>>(iter: I) -> Self {
+        Self {assigned: iter.into_iter().collect()}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn symbols() {
+        assert_eq!(generated(0), "A");
+        assert_eq!(generated(25), "Z");
+        assert_eq!(generated(26), "AA");
+        assert_eq!(generated(27), "AB");
+        assert_eq!(generated(26 + 26 * 26), "AAA");
+
+        let mut symbols = Symbols::new();
+        symbols.assign(vec![3, 1, 3]);
+        assert_eq!(symbols.get(&3), "A");
+        assert_eq!(symbols.get(&1), "B");
+        assert_eq!(symbols.get(&2), UNDEFINED_SYMBOL);
+        assert_eq!(symbols.set(2, "A".to_string()), Err("Symbol \"A\" is used by another color".to_string()));
+        assert_eq!(symbols.set(3, " * ".to_string()), Ok(Some("A".to_string())));
+        symbols.assign(0..30);
+        assert_eq!(symbols.get(&0), "A");
+        assert_eq!(symbols.get(&3), "*");
+        assert_eq!(symbols.get(&29), "AC");
+        assert_eq!(symbols.assigned().len(), 30);
+    }
+}
@@ -3,20 +3,19 @@ pub use iced::{
     scrollable,
     slider,
     text_input,
-    checkbox,
     Checkbox,
     Button,
     Text,
     Column,
-    Sandbox,
+    Application,
+    Command,
+    executor,
     Settings,
     Element,
     Row,
     Length,
     Space,
     Container,
-    Point,
-    Background,
     Scrollable,
     Align,
     Slider,
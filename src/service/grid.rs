@@ -1,4 +1,3 @@
-use std::num::NonZeroUsize;
 use core::mem;
 use std::sync::Arc;
 use crate::model::*;
@@ -9,24 +8,74 @@ pub enum Message<T: ColorTrait> {
     Undo,
     Redo,
     Draw(Coord),
+    StartStroke(Coord),
+    EndStroke,
+    Compound(Vec<Message<T>>),
     Grow(Side),
     Shrink(Side),
     Resize(Size),
     Updated(Arc<Model<T>>),
     Loaded(Arc<Model<T>>),
+    /// brings back the model with its filled flags, undo of changes that reset them
+    Restore(Arc<Model<T>>),
     ToggleLineItem(usize),
     SchemaChange,
+    SetSchema(Schema),
+    SetPalette(Palette<T>),
+    SetSymbol(T, String),
+    SetSymbols(Symbols<T>),
     ActivateColor(T),
     AddColor(T),
+    /// adds the color picked from a catalog and activates it
+    AddCatalogColor(T, ColorInfo),
+    MoveColor(usize, usize),
+    /// replaces pattern colors with the catalog ones: from, to and the catalog info
+    SnapColors(Vec<(T, T, ColorInfo)>),
+    /// merges colors into others, all at once
+    MergeColors(Vec<(T, T)>),
+    ReplaceColor(T, T),
+    SwapColors(T, T),
+    /// sets colors of the beads keeping their filled flags
+    Recolor(Vec<(Coord, T)>),
     RemoveColor,
     DrawColor(Coord, T),
+    Fill(Coord),
+    DrawShape(Shape, Coord, Coord),
+    Paint(Vec<(Coord, T)>),
+    Transform(Transform),
+    TransformArea(Transform, Coord, Coord),
     MoveSeam(isize),
+    Jump(isize),
+    SetHistoryDepth(usize),
+}
+
+pub const DEFAULT_HISTORY_DEPTH: usize = 100;
+
+/// Labels of undo and redo entries, the last ones are the closest to the current state
+#[derive(Debug, Clone)]
+pub struct History {
+    pub undo: Vec<String>,
+    pub redo: Vec<String>,
+    pub depth: usize,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            depth: DEFAULT_HISTORY_DEPTH,
+        }
+    }
 }
 
 pub struct Service<T: ColorTrait> {
     model: Model<T>,
-    undo: Vec<Message<T>>,
-    redo: Vec<Message<T>>,
+    undo: Vec<(String, Message<T>)>,
+    redo: Vec<(String, Message<T>)>,
+    transaction: Option<Vec<Message<T>>>,
+    label: Option<String>,
+    depth: usize,
 }
 
 impl<T: ColorTrait> Service<T> {
@@ -35,15 +84,120 @@ impl<T: ColorTrait> Service<T> {
             model,
             undo: Vec::new(),
             redo: Vec::new(),
+            transaction: None,
+            label: None,
+            depth: DEFAULT_HISTORY_DEPTH,
+        }
+    }
+    pub fn history(&self) -> History {
+        History {
+            undo: self.undo.iter().map(|(label, _)|label.clone()).collect(),
+            redo: self.redo.iter().map(|(label, _)|label.clone()).collect(),
+            depth: self.depth,
         }
     }
     fn updated(&self) -> Message<T> {
         Message::Updated(Arc::new(self.model.clone()))
     }
     fn push_undo(&mut self, msg: Message<T>) {
-        self.undo.push(msg);
+        match self.transaction.as_mut() {
+            Some(transaction) => transaction.push(msg),
+            None => {
+                let label = self.label.clone().unwrap_or_else(||self.describe(&msg));
+                self.undo.push((label, msg));
+                self.trim();
+            },
+        }
         self.redo.clear();
     }
+    fn trim(&mut self) {
+        if self.undo.len() > self.depth {
+            let excess = self.undo.len() - self.depth;
+            self.undo.drain(..excess);
+        }
+    }
+    /// Undo entry with its own label, unless it is a replay which keeps the original one
+    fn push_labeled_undo(&mut self, label: &str, msg: Message<T>) {
+        let own = self.label.is_none();
+        if own {
+            self.label = Some(label.to_string());
+        }
+        self.push_undo(msg);
+        if own {
+            self.label = None;
+        }
+    }
+    /// Replaces the colors in beads and palette as a single undo step
+    fn replace_colors(&mut self, label: &str, mapping: Vec<(T, T, Option<ColorInfo>)>) -> Result<Message<T>, String> {
+        use Message::*;
+        let prev_palette = self.model.palette().clone();
+        let prev_symbols = self.model.symbols().clone();
+        let replace = mapping.iter().map(|(from, to, _)|(from.clone(), to.clone())).collect();
+        let prev = self.model.replace_colors(&replace)?;
+        mapping.into_iter()
+            .filter_map(|(_, to, info)|info.map(|info|(to, info)))
+            .for_each(|(to, info)|self.model.set_color_info(to, info));
+        self.push_labeled_undo(label, Compound(vec![
+            Recolor(prev), SetPalette(prev_palette), SetSymbols(prev_symbols)
+        ]));
+        Ok(self.updated())
+    }
+    /// Human readable label of the action reverted by the undo entry.
+    /// Called after the action is applied, so the model holds the new state
+    fn describe(&self, undo: &Message<T>) -> String {
+        use Message::*;
+        let side = |side: &Side| format!("{:?}", side).to_lowercase();
+        let beads = |count: usize| if count == 1 { "1 bead".to_string() } else { format!("{} beads", count) };
+        match undo {
+            DrawColor(..) => format!("Drew {}", beads(1)),
+            Compound(entries) if entries.iter().all(|entry|matches!(entry, DrawColor(..))) => {
+                format!("Drew {}", beads(entries.len()))
+            },
+            Compound(entries) => format!("{} changes", entries.len()),
+            Paint(items) | Recolor(items) => format!("Changed {}", beads(items.len())),
+            Shrink(s) => format!("Grew {}", side(s)),
+            Grow(s) => format!("Shrank {}", side(s)),
+            Resize(_) => {
+                let size = self.model.size();
+                format!("Resized to {}×{}", size.width(), size.height())
+            },
+            ToggleLineItem(index) => format!("Toggled line item {}", index + 1),
+            SetPalette(_) => "Palette changed".to_string(),
+            SetSymbols(_) => "Symbols changed".to_string(),
+            Loaded(_) => "Loaded pattern".to_string(),
+            msg => format!("{:?}", msg),
+        }
+    }
+    /// Undo entry restoring the model as it was before the change
+    fn push_snapshot(&mut self, label: &str, prev: Model<T>) {
+        self.push_labeled_undo(label, Message::Restore(Arc::new(prev)));
+    }
+    /// New colors go next to the activated one
+    fn insert_next_to_activated(&mut self, color: T) {
+        let palette = self.model.palette();
+        let index = palette.position(palette.activated()).map_or(palette.colors().len(), |index|index + 1);
+        self.model.insert_color(index, color);
+    }
+    /// Closes the transaction, all collected entries become a single undo step
+    fn commit(&mut self) {
+        if let Some(mut transaction) = self.transaction.take() {
+            if !transaction.is_empty() {
+                transaction.reverse();
+                self.push_undo(Message::Compound(transaction));
+            }
+        }
+    }
+}
+
+impl<T: Default + ColorTrait> Service<T> {
+    /// Applies an undo or redo entry, the entry created in return keeps its label
+    fn replay(&mut self, label: String, msg: Message<T>) -> Result<Option<Message<T>>, String> {
+        use super::Service as _;
+        let prev = self.label.replace(label);
+        let result = self.service(msg);
+        self.label = prev;
+        result
+    }
 }
 
 impl<T: Default + ColorTrait> super::Service for Service<T> {
@@ -60,6 +214,29 @@ impl<T: Default + ColorTrait> super::Service for Service<T> {
                             Some(self.updated())
                         })
                     })?,
+            StartStroke(coord) => {
+                self.commit();
+                self.transaction = Some(Vec::new());
+                self.service(Draw(coord))?
+            },
+            EndStroke => {
+                let stroke = self.transaction.as_ref().is_some_and(|t|!t.is_empty());
+                self.commit();
+                // the stroke shows up in the history only now
+                if stroke { Some(self.updated()) } else { None }
+            },
+            Compound(messages) => {
+                let outer = self.transaction.replace(Vec::new());
+                let result = messages.into_iter().try_for_each(|msg|self.service(msg).map(|_|()));
+                let inner = mem::replace(&mut self.transaction, outer);
+                let changed = inner.as_ref().is_some_and(|inner|!inner.is_empty());
+                if let Some(mut inner) = inner.filter(|inner|!inner.is_empty()) {
+                    inner.reverse();
+                    self.push_undo(Compound(inner));
+                }
+                result?;
+                if changed { Some(self.updated()) } else { None }
+            },
             Grow(side) => {
                 self.model.grow(side, Default::default());
                 self.push_undo(Shrink(side));
@@ -72,51 +249,129 @@ impl<T: Default + ColorTrait> super::Service for Service<T> {
             },
             Resize(size) => {
                 let prev = self.model.size();
-                self.push_undo(Resize(prev));
                 self.model.resize(size);
+                self.push_undo(Resize(prev));
                 Some(self.updated())
             },
             ToggleLineItem(index) => {
                 self.model.toggle_filled(index)?;
+                self.push_undo(ToggleLineItem(index));
                 Some(self.updated())
             },
             SchemaChange => {
                 let schema = self.model.schema();
-                self.model.set_schema(schema.switch());
+                self.service(SetSchema(schema.switch()))?
+            },
+            SetSchema(schema) => {
+                let prev = self.model.clone();
+                self.model.set_schema(schema);
+                self.push_snapshot(&format!("Schema changed to {:?}", schema), prev);
+                Some(self.updated())
+            },
+            SetPalette(palette) => {
+                let prev = self.model.set_palette(palette);
+                self.push_undo(SetPalette(prev));
+                Some(self.updated())
+            },
+            SetSymbol(color, symbol) => {
+                let prev = self.model.symbols().clone();
+                self.model.set_symbol(color, symbol)?;
+                self.push_undo(SetSymbols(prev));
+                Some(self.updated())
+            },
+            SetSymbols(symbols) => {
+                let prev = self.model.set_symbols(symbols);
+                self.push_undo(SetSymbols(prev));
                 Some(self.updated())
             },
             Undo => {
+                self.commit();
                 let mut undo = Vec::new();
                 mem::swap(&mut self.undo, &mut undo);
                 mem::swap(&mut self.undo, &mut self.redo);
                 let result = match undo.pop() {
                     None => Err("Undo is empty".to_string()),
-                    Some(msg) => self.service(msg),
+                    Some((label, msg)) => self.replay(label, msg),
                 };
                 mem::swap(&mut self.undo, &mut self.redo);
                 mem::swap(&mut self.undo, &mut undo);
                 result?
             },
             Redo => {
+                self.commit();
                 let mut redo = Vec::new();
                 mem::swap(&mut self.redo, &mut redo);
                 let result = match redo.pop() {
                     None => Err("Redo is empty".to_string()),
-                    Some(msg) => self.service(msg),
+                    Some((label, msg)) => self.replay(label, msg),
                 };
                 mem::swap(&mut self.redo, &mut redo);
                 result?
             },
             Loaded(model) => {
-                self.model = model.as_ref().clone();
+                let prev = mem::replace(&mut self.model, model.as_ref().clone());
+                self.push_undo(Loaded(Arc::new(prev)));
                 Some(Loaded(model))
             },
+            Restore(model) => {
+                let prev = mem::replace(&mut self.model, model.as_ref().clone());
+                self.push_undo(Restore(Arc::new(prev)));
+                Some(self.updated())
+            },
             AddColor(color) => {
-                self.model.add_color(color);
+                let prev = self.model.palette().clone();
+                self.insert_next_to_activated(color);
+                self.push_undo(SetPalette(prev));
+                Some(self.updated())
+            },
+            AddCatalogColor(color, info) => {
+                let prev = self.model.palette().clone();
+                self.insert_next_to_activated(color.clone());
+                self.model.set_color_info(color.clone(), info);
+                self.model.activate_color(color);
+                self.push_undo(SetPalette(prev));
+                Some(self.updated())
+            },
+            MoveColor(from, to) => {
+                let prev = self.model.palette().clone();
+                self.model.move_color(from, to)?;
+                self.push_undo(SetPalette(prev));
                 Some(self.updated())
             },
+            SnapColors(mapping) => {
+                let mapping = mapping.into_iter().map(|(from, to, info)|(from, to, Some(info))).collect();
+                Some(self.replace_colors("Snapped colors to catalog", mapping)?)
+            },
+            MergeColors(mapping) => {
+                let label = format!("Merged {} colors", mapping.len());
+                let mapping = mapping.into_iter().map(|(from, to)|(from, to, None)).collect();
+                Some(self.replace_colors(&label, mapping)?)
+            },
+            ReplaceColor(from, to) => {
+                Some(self.replace_colors("Replaced color", vec![(from, to, None)])?)
+            },
+            SwapColors(first, second) => {
+                let prev = self.model.swap_colors(first, second)?;
+                if prev.is_empty() {
+                    None
+                } else {
+                    self.push_labeled_undo("Swapped colors", Recolor(prev));
+                    Some(self.updated())
+                }
+            },
+            Recolor(items) => {
+                let prev = self.model.recolor(items)?;
+                if prev.is_empty() {
+                    None
+                } else {
+                    self.push_undo(Recolor(prev));
+                    Some(self.updated())
+                }
+            },
             RemoveColor => {
+                let prev = self.model.palette().clone();
                 self.model.remove_color();
+                self.push_undo(SetPalette(prev));
                 Some(self.updated())
             },
             ActivateColor(color) => {
@@ -132,10 +387,76 @@ impl<T: Default + ColorTrait> super::Service for Service<T> {
                 self.model.activate_color(prev_activated);
                 Some(self.updated())
             }
+            Fill(Coord{x, y}) => {
+                let prev = self.model.fill(x, y)?;
+                if prev.is_empty() {
+                    None
+                } else {
+                    self.push_undo(Paint(prev));
+                    Some(self.updated())
+                }
+            },
+            DrawShape(shape, from, to) => {
+                let prev = self.model.draw_shape(shape, from, to)?;
+                if prev.is_empty() {
+                    None
+                } else {
+                    self.push_undo(Paint(prev));
+                    Some(self.updated())
+                }
+            },
+            Transform(transform) => {
+                let prev = self.model.clone();
+                self.model.transform(transform);
+                let label = match transform {
+                    crate::model::Transform::Mirror => "Mirrored",
+                    crate::model::Transform::Flip => "Flipped",
+                    crate::model::Transform::Turn => "Turned",
+                };
+                self.push_snapshot(label, prev);
+                Some(self.updated())
+            },
+            TransformArea(transform, from, to) => {
+                let prev = self.model.transform_area(transform, from, to)?;
+                if prev.is_empty() {
+                    None
+                } else {
+                    self.push_undo(Paint(prev));
+                    Some(self.updated())
+                }
+            },
+            Paint(items) => {
+                let prev = self.model.paint(items)?;
+                if prev.is_empty() {
+                    None
+                } else {
+                    self.push_undo(Paint(prev));
+                    Some(self.updated())
+                }
+            },
             MoveSeam(direction) => {
+                let prev = self.model.clone();
                 self.model.rotate(direction);
+                self.push_snapshot(&format!("Moved seam by {}", direction), prev);
                 Some(self.updated())
-            }
+            },
+            Jump(steps) => {
+                let msg = if steps < 0 { Undo } else { Redo };
+                let mut result = None;
+                for _ in 0..steps.abs() {
+                    result = self.service(msg.clone())?;
+                }
+                result
+            },
+            SetHistoryDepth(depth) => {
+                self.depth = depth.max(1);
+                self.trim();
+                if self.redo.len() > self.depth {
+                    let excess = self.redo.len() - self.depth;
+                    self.redo.drain(..excess);
+                }
+                Some(self.updated())
+            },
             Updated(_) | Ignore => None,
         })
     }
@@ -145,6 +466,7 @@ impl<T: Default + ColorTrait> super::Service for Service<T> {
 mod test {
     use super::*;
     use crate::service::Service as _;
+    use std::num::NonZeroUsize;
 
     fn make() -> Service<u8> {
         let mut model = Model::default();
@@ -200,4 +522,246 @@ mod test {
             _ => {panic!(format!("unexpected response: {:?}", response))},
         }
     }
+
+    fn colors(s: &Service<u8>) -> Vec<Vec<u8>> {
+        s.model.grid().as_table_iter()
+            .map(|row|row.map(|Bead{color, ..}|*color).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_fill() {
+        let size = Size {
+            width: NonZeroUsize::new(4).unwrap(),
+            height: NonZeroUsize::new(3).unwrap(),
+        };
+        let mut model = Model::default();
+        model.resize(size);
+        model.set_schema(Schema::Straight);
+        let mut s = Service::new(model);
+        s.service(Message::Paint(vec![
+            (Coord{x: 0, y: 1}, 1), (Coord{x: 1, y: 1}, 1), (Coord{x: 2, y: 1}, 1),
+            (Coord{x: 0, y: 2}, 1), (Coord{x: 1, y: 2}, 1), (Coord{x: 2, y: 2}, 1),
+        ])).unwrap();
+        s.service(Message::ActivateColor(2)).unwrap();
+        s.service(Message::Fill(Coord{x: 1, y: 0})).unwrap();
+        assert_eq!(colors(&s), vec![vec![2, 1, 1, 2]; 3]);
+        s.service(Message::Undo).unwrap();
+        assert_eq!(colors(&s), vec![vec![0, 1, 1, 0]; 3]);
+        s.service(Message::Redo).unwrap();
+        assert_eq!(colors(&s), vec![vec![2, 1, 1, 2]; 3]);
+
+        let mut model = Model::default();
+        model.resize(size);
+        let mut s = Service::new(model);
+        s.service(Message::Paint(vec![
+            (Coord{x: 0, y: 1}, 1), (Coord{x: 1, y: 2}, 1), (Coord{x: 2, y: 1}, 1),
+        ])).unwrap();
+        s.service(Message::ActivateColor(2)).unwrap();
+        s.service(Message::Fill(Coord{x: 0, y: 1})).unwrap();
+        assert_eq!(colors(&s), vec![vec![0, 2, 0, 0], vec![0, 0, 2, 0], vec![0, 2, 0, 0]]);
+    }
+
+    #[test]
+    fn test_shape_across_seam() {
+        let mut model = Model::default();
+        model.resize(Size {
+            width: NonZeroUsize::new(4).unwrap(),
+            height: NonZeroUsize::new(3).unwrap(),
+        });
+        let mut s = Service::new(model);
+        s.service(Message::ActivateColor(1)).unwrap();
+        s.service(Message::DrawShape(Shape::Line, Coord{x: 0, y: 2}, Coord{x: 2, y: 4})).unwrap();
+        assert_eq!(colors(&s), vec![vec![0, 0, 1, 0], vec![0, 0, 0, 1], vec![1, 0, 0, 0]]);
+        s.service(Message::Undo).unwrap();
+        assert_eq!(colors(&s), vec![vec![0; 4]; 3]);
+    }
+
+    /// Colors of the beads with their neighbours, the rows above `top` are left out
+    fn neighbours(s: &Service<u8>, top: usize) -> Vec<Vec<u8>> {
+        let grid = s.model.grid();
+        let mut result: Vec<Vec<u8>> = grid.as_table_iter().enumerate()
+            .skip(top)
+            .flat_map(|(x, row)|row.enumerate().map(move |(y, _)|Coord {x, y}))
+            .map(|coord| {
+                let mut colors: Vec<_> = s.model.schema().neighbours(coord, grid.size()).into_iter()
+                    .filter(|&Coord {x, ..}|x >= top)
+                    .map(|Coord {x, y}|grid.get(x, y).unwrap().color)
+                    .collect();
+                colors.sort();
+                colors.insert(0, grid.get(coord.x, coord.y).unwrap().color);
+                colors
+            })
+            .collect();
+        result.sort();
+        result
+    }
+
+    #[test]
+    fn test_transform() {
+        for &(schema, height) in &[(Schema::FirstOffset, 4), (Schema::SecondOffset, 5), (Schema::Straight, 4)] {
+            let mut model = Model::default();
+            model.resize(Size {
+                width: NonZeroUsize::new(4).unwrap(),
+                height: NonZeroUsize::new(height).unwrap(),
+            });
+            model.set_schema(schema);
+            let mut s = Service::new(model);
+            let items = (0..height).map(|x|(0..4).map(move |y|(Coord{x, y}, (x * 4 + y + 1) as u8)))
+                .flatten()
+                .collect();
+            s.service(Message::Paint(items)).unwrap();
+            let origin = colors(&s);
+            let origin_neighbours = neighbours(&s, 0);
+            for &transform in &[Transform::Mirror, Transform::Flip, Transform::Turn] {
+                s.service(Message::Transform(transform)).unwrap();
+                assert_ne!(colors(&s), origin);
+                assert_eq!(neighbours(&s, 0), origin_neighbours, "{:?} {:?}", schema, transform);
+                s.service(Message::Undo).unwrap();
+                assert_eq!(colors(&s), origin);
+                assert_eq!(s.model.schema(), schema);
+            }
+            // the selection keeps the schema, the shifted rows move to keep the neighbours
+            let (from, to) = (Coord {x: 0, y: 0}, Coord {x: height - 1, y: 3});
+            for &transform in &[Transform::Mirror, Transform::Flip, Transform::Turn] {
+                s.service(Message::TransformArea(transform, from, to)).unwrap();
+                assert_ne!(colors(&s), origin);
+                assert_eq!(s.model.schema(), schema);
+                assert_eq!(neighbours(&s, 0), origin_neighbours, "area {:?} {:?}", schema, transform);
+                s.service(Message::Undo).unwrap();
+                assert_eq!(colors(&s), origin);
+            }
+            // the rows of the other parity are shifted when the selection starts on the second row
+            let (from, to) = (Coord {x: 1, y: 0}, Coord {x: height - 1, y: 3});
+            let inner_neighbours = neighbours(&s, 1);
+            for &transform in &[Transform::Mirror, Transform::Flip, Transform::Turn] {
+                s.service(Message::TransformArea(transform, from, to)).unwrap();
+                assert_eq!(neighbours(&s, 1), inner_neighbours, "lower area {:?} {:?}", schema, transform);
+                s.service(Message::Undo).unwrap();
+            }
+        }
+    }
+
+    fn state(s: &Service<u8>) -> (Vec<Vec<u8>>, Schema, Vec<(Bead<u8>, usize)>, Vec<(u8, bool)>, Vec<(u8, String)>) {
+        let palette = s.model.palette();
+        let colors_state: Vec<_> = palette.colors().iter().map(|&c|(c, &c == palette.activated())).collect();
+        let mut symbols: Vec<_> = s.model.symbols().assigned().iter().map(|(&c, symbol)|(c, symbol.clone())).collect();
+        symbols.sort();
+        (colors(s), s.model.schema(), s.model.line().line().clone(), colors_state, symbols)
+    }
+
+    #[test]
+    fn test_undo_every_message() {
+        let mut loaded = Model::default();
+        loaded.add_color(7);
+        let messages = vec![
+            Message::SchemaChange,
+            Message::SetSchema(Schema::Straight),
+            Message::MoveSeam(3),
+            Message::Transform(Transform::Mirror),
+            Message::Transform(Transform::Flip),
+            Message::Transform(Transform::Turn),
+            Message::ToggleLineItem(2),
+            Message::AddColor(5),
+            Message::MoveColor(0, 1),
+            Message::AddCatalogColor(9, ColorInfo {name: "Nine".to_string(), ..Default::default()}),
+            Message::RemoveColor,
+            Message::SnapColors(vec![(0, 4, Default::default()), (3, 0, Default::default())]),
+            Message::MergeColors(vec![(3, 0)]),
+            Message::ReplaceColor(0, 8),
+            Message::SwapColors(3, 0),
+            Message::SetSymbol(0, "*".to_string()),
+            Message::Loaded(Arc::new(loaded)),
+        ];
+        for msg in messages {
+            let mut s = make();
+            s.service(Message::Draw(Coord{ x: 0, y: 0 })).unwrap();
+            // stringing progress must survive the undo of changes that reset it
+            s.service(Message::ToggleLineItem(0)).unwrap();
+            s.service(Message::ToggleLineItem(1)).unwrap();
+            let before = state(&s);
+            assert!(before.2[..2].iter().all(|(bead, _)|bead.filled));
+            s.service(msg.clone()).unwrap();
+            let after = state(&s);
+            assert_ne!(before, after, "{:?} changes nothing", msg);
+            s.service(Message::Undo).unwrap();
+            assert_eq!(before, state(&s), "{:?} is not undone", msg);
+            s.service(Message::Redo).unwrap();
+            assert_eq!(after, state(&s), "{:?} is not redone", msg);
+        }
+    }
+
+    #[test]
+    fn test_replace_keeps_progress() {
+        let size = Size {
+            width: NonZeroUsize::new(3).unwrap(),
+            height: NonZeroUsize::new(2).unwrap(),
+        };
+        let mut model = Model::default();
+        model.resize(size);
+        model.set_schema(Schema::Straight);
+        let mut s = Service::new(model);
+        s.service(Message::Paint(vec![(Coord{x: 0, y: 1}, 1), (Coord{x: 1, y: 1}, 2)])).unwrap();
+        s.service(Message::ToggleLineItem(0)).unwrap();
+        s.service(Message::ToggleLineItem(1)).unwrap();
+        let filled = |s: &Service<u8>|s.model.line().line().iter().map(|(bead, _)|bead.filled).collect::<Vec<_>>();
+        let before = filled(&s);
+        assert_eq!(before[..2], [true, true]);
+
+        s.service(Message::SwapColors(0, 1)).unwrap();
+        assert_eq!(filled(&s), before);
+        s.service(Message::ReplaceColor(2, 5)).unwrap();
+        assert_eq!(filled(&s), before);
+        assert!(!s.model.palette().contains(&2));
+        assert_eq!(s.history().undo.last().unwrap(), "Replaced color");
+        s.service(Message::Undo).unwrap();
+        s.service(Message::Undo).unwrap();
+        assert_eq!(filled(&s), before);
+        assert_eq!(s.model.grid().get(0, 1).unwrap().color, 1);
+    }
+
+    #[test]
+    fn test_stroke_undo() {
+        let mut s = make();
+        let before = colors(&s);
+        let undo_len = s.undo.len();
+        s.service(Message::ActivateColor(33)).unwrap();
+        s.service(Message::StartStroke(Coord{ x: 0, y: 0 })).unwrap();
+        s.service(Message::Draw(Coord{ x: 0, y: 1 })).unwrap();
+        s.service(Message::Draw(Coord{ x: 1, y: 1 })).unwrap();
+        s.service(Message::EndStroke).unwrap();
+        let after = colors(&s);
+        assert_eq!(s.undo.len(), undo_len + 1);
+        assert_eq!(s.history().undo.last().unwrap(), "Drew 3 beads");
+        s.service(Message::Undo).unwrap();
+        assert_eq!(colors(&s), before);
+        s.service(Message::Redo).unwrap();
+        assert_eq!(colors(&s), after);
+        s.service(Message::Undo).unwrap();
+        assert_eq!(colors(&s), before);
+    }
+
+    #[test]
+    fn test_history() {
+        let mut s = make();
+        s.service(Message::SetHistoryDepth(3)).unwrap();
+        assert_eq!(s.history().undo, vec!["Grew left", "Grew top", "Grew left"]);
+        s.service(Message::Resize(Size {
+            width: NonZeroUsize::new(20).unwrap(),
+            height: NonZeroUsize::new(40).unwrap(),
+        })).unwrap();
+        s.service(Message::Shrink(Side::Top)).unwrap();
+        assert_eq!(s.history().undo, vec!["Grew left", "Resized to 20×40", "Shrank top"]);
+        s.service(Message::Jump(-2)).unwrap();
+        assert_eq!(s.model.size().width(), 42);
+        assert_eq!(s.history().undo, vec!["Grew left"]);
+        assert_eq!(s.history().redo, vec!["Shrank top", "Resized to 20×40"]);
+        s.service(Message::Jump(2)).unwrap();
+        assert_eq!(s.model.size().height(), 39);
+        assert_eq!(s.history().undo, vec!["Grew left", "Resized to 20×40", "Shrank top"]);
+        s.service(Message::SnapColors(vec![(0, 1, Default::default())])).unwrap();
+        s.service(Message::Undo).unwrap();
+        s.service(Message::Redo).unwrap();
+        assert_eq!(s.history().undo.last().unwrap(), "Snapped colors to catalog");
+    }
 }
\ No newline at end of file
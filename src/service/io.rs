@@ -1,18 +1,37 @@
 use std::sync::Arc;
 use std::path::PathBuf;
 use crate::model::*;
+use crate::io::{Catalog, Document, FileType, ExportOptions, ImageOptions, PaletteFormat, Quantization};
 
 #[derive(Debug, Clone)]
 pub enum Message {
-    Open(PathBuf),
-    Save(PathBuf),
-    Loaded(Arc<Model<Color>>),
+    /// pattern file and the color of blank CSV cells
+    Open(PathBuf, Color),
+    Save(PathBuf, FileType, ExportOptions),
+    Import(PathBuf, ImageOptions),
+    OpenCatalog(PathBuf),
+    CatalogLoaded(Arc<Catalog>),
+    OpenPalette(PathBuf),
+    PaletteLoaded(Palette<Color>),
+    /// the palette shown on start
+    SaveDefaultPalette,
+    Loaded(Arc<Model<Color>>, ViewState),
     GridUpdated(Arc<Model<Color>>),
+    ViewUpdated(ViewState),
     Ignore,
 }
-#[derive(Default)]
 pub struct Service {
-    model: Arc<Model<Color>>
+    model: Arc<Model<Color>>,
+    view: ViewState,
+}
+
+impl Service {
+    pub fn new(model: Arc<Model<Color>>) -> Self {
+        Self {
+            model,
+            view: Default::default(),
+        }
+    }
 }
 
 impl super::Service for Service {
@@ -21,28 +40,62 @@ impl super::Service for Service {
     fn service(&mut self, msg: Self::Message) -> Result<Option<Self::Message>, String> {
         use Message::*;
         Ok( match msg {
-            Open(path) => {
-                let model = Arc::new(match crate::io::load_line(&path) {
-                    Ok(line) => {
-                        Model::from(line)
-                    }
-                    Err(_e) => {
-                        let grid = crate::io::load_grid(&path)?;
-                        Model::from(grid)
-                    }
-                });
+            Open(path, background) => {
+                let (model, view) = crate::io::load(&path, background)?.into_model();
+                let model = Arc::new(model);
                 self.model = model.clone();
-                Some(Loaded(model))
+                Some(Loaded(model, view))
+            },
+            Import(path, options) => {
+                let palette = self.model.palette().colors().to_vec();
+                let schema = self.model.schema();
+                let grid = crate::io::load_image(&path, options, schema, &palette)?;
+                let mut model = Model::from(grid);
+                model.set_schema(schema);
+                if options.quantization == Quantization::Palette {
+                    palette.into_iter().for_each(|color|model.add_color(color));
+                }
+                let model = Arc::new(model);
+                self.model = model.clone();
+                Some(Loaded(model, ViewState::default()))
+            },
+            OpenCatalog(path) => Some(CatalogLoaded(Arc::new(crate::io::load_catalog(&path)?))),
+            OpenPalette(path) => {
+                let mut palette = crate::io::load_palette(&path)?;
+                // colors of the pattern missing in the file go to its end, as loaded patterns keep them
+                self.model.line().line().iter().for_each(|(bead, _)|palette.add_color(bead.color));
+                Some(PaletteLoaded(palette))
             },
-            Save(path) => {
-                crate::io::save(&path, self.model.line())?;
+            SaveDefaultPalette => {
+                crate::io::save_default_palette(self.model.palette())?;
+                None
+            },
+            Save(path, file_type, options) => {
+                match file_type {
+                    FileType::Pattern => crate::io::save(&path, &Document::new(&self.model, self.view))?,
+                    FileType::GridCsv => crate::io::save_grid(&path, self.model.grid())?,
+                    FileType::LineCsv => crate::io::save_line(&path, self.model.line(), self.model.palette())?,
+                    FileType::LineText => crate::io::save_stringing(&path, &self.model, options.text)?,
+                    FileType::Chart => crate::io::save_chart(
+                        &path, self.model.grid(), self.model.schema(), self.view.rotation, options.chart
+                    )?,
+                    FileType::SheetSvg => crate::io::save_sheet_svg(&path, &self.model, self.view.rotation)?,
+                    FileType::SheetPdf => crate::io::save_sheet_pdf(&path, &self.model, self.view.rotation)?,
+                    FileType::PaletteGpl => crate::io::save_palette(&path, self.model.palette(), PaletteFormat::Gpl)?,
+                    FileType::PalettePaintNet => crate::io::save_palette(&path, self.model.palette(), PaletteFormat::PaintNet)?,
+                    FileType::PaletteJson => crate::io::save_palette(&path, self.model.palette(), PaletteFormat::Json)?,
+                }
                 None
             },
             GridUpdated(model) => {
                 self.model = model;
                 None
             },
-            Ignore | Loaded(_) => None,
+            ViewUpdated(view) => {
+                self.view = view;
+                None
+            },
+            Ignore | Loaded(..) | CatalogLoaded(_) | PaletteLoaded(_) => None,
         })
     }
-}
\ No newline at end of file
+}
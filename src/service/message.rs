@@ -14,18 +14,36 @@ impl From<Message> for GridServiceMessage<Color> {
         use Message::*;
         use GridServiceMessage as GSMsg;
         match msg {
-            Grid(GMsg::Move(coord)) | Grid(GMsg::Press(coord)) => GSMsg::Draw(coord),
+            Grid(GMsg::Move(coord)) => GSMsg::Draw(coord),
+            Grid(GMsg::Press(coord)) => GSMsg::StartStroke(coord),
+            MouseRelease => GSMsg::EndStroke,
+            Grid(GMsg::Fill(coord)) => GSMsg::Fill(coord),
+            Grid(GMsg::DrawShape(shape, from, to)) => GSMsg::DrawShape(shape, from, to),
+            Grid(GMsg::Cut(items)) | Grid(GMsg::Paste(items)) => GSMsg::Paint(items),
+            Grid(GMsg::TransformArea(transform, from, to)) => GSMsg::TransformArea(transform, from, to),
             LeftPanel(LPMsg::Grow(side)) => GSMsg::Grow(side),
             LeftPanel(LPMsg::Shrink(side)) => GSMsg::Shrink(side),
             LeftPanel(LPMsg::Resize(size)) => GSMsg::Resize(size),
             LeftMenu(LMMsg::SchemaChange) => GSMsg::SchemaChange,
             Message::LeftMenu(LMMsg::MoveSeam(x)) => GSMsg::MoveSeam(x),
+            LeftMenu(LMMsg::Transform(transform)) => GSMsg::Transform(transform),
             RightPanel(RPMsg::ToggleCheckbox(index)) => GSMsg::ToggleLineItem(index),
+            RightPanel(RPMsg::SetSymbol(color, symbol)) => GSMsg::SetSymbol(color, symbol),
             RightPanel(RPMsg::AddColor(color)) => GSMsg::AddColor(color),
+            RightPanel(RPMsg::PickCatalogColor(color, info)) => GSMsg::AddCatalogColor(color, info),
+            RightPanel(RPMsg::SnapColors(mapping)) => GSMsg::SnapColors(mapping),
+            RightPanel(RPMsg::MergeColors(mapping)) => GSMsg::MergeColors(mapping),
+            RightPanel(RPMsg::ReplaceColor(from, to)) => GSMsg::ReplaceColor(from, to),
+            RightPanel(RPMsg::SwapColors(first, second)) => GSMsg::SwapColors(first, second),
             RightPanel(RPMsg::RemoveColor) => GSMsg::RemoveColor,
+            RightPanel(RPMsg::Jump(steps)) => GSMsg::Jump(steps),
+            RightPanel(RPMsg::SetHistoryDepth(depth)) => GSMsg::SetHistoryDepth(depth),
             TopMenu(TMMsg::Undo) => GSMsg::Undo,
             TopMenu(TMMsg::Redo) => GSMsg::Redo,
-            TopMenu(TMMsg::Palette(PMsg::ActivateColor(color))) => GSMsg::ActivateColor(color),
+            Grid(GMsg::Pick(color)) |
+            TopMenu(TMMsg::Palette(PMsg::ActivateColor(color))) |
+            TopMenu(TMMsg::Palette(PMsg::Grab(_, color))) => GSMsg::ActivateColor(color),
+            TopMenu(TMMsg::Palette(PMsg::MoveColor(from, to))) => GSMsg::MoveColor(from, to),
             _ => GSMsg::Ignore
         }
     }
@@ -45,8 +63,13 @@ impl From<Message> for IOMessage {
     fn from(msg: Message) -> Self {
         use Message::*;
         match msg {
-            LeftPanel(LPMsg::FS(FMsg::Open(path))) => IOMessage::Open(path),
-            LeftPanel(LPMsg::FS(FMsg::Save(path))) => IOMessage::Save(path),
+            LeftPanel(LPMsg::FS(FMsg::Open(path, background))) => IOMessage::Open(path, background),
+            LeftPanel(LPMsg::FS(FMsg::OpenCatalog(path))) => IOMessage::OpenCatalog(path),
+            LeftPanel(LPMsg::FS(FMsg::OpenPalette(path))) => IOMessage::OpenPalette(path),
+            RightPanel(RPMsg::SaveDefaultPalette) => IOMessage::SaveDefaultPalette,
+            LeftPanel(LPMsg::FS(FMsg::Save(path, file_type, options))) => IOMessage::Save(path, file_type, options),
+            LeftPanel(LPMsg::FS(FMsg::Import(path, options))) => IOMessage::Import(path, options),
+            ViewUpdated(view) => IOMessage::ViewUpdated(view),
             _=> IOMessage::Ignore
         }
     }
@@ -56,7 +79,8 @@ impl From<IOMessage> for GridServiceMessage<Color> {
     fn from(msg: IOMessage) -> Self {
         use GridServiceMessage::*;
         match msg {
-            IOMessage::Loaded(grid) => Loaded(grid),
+            IOMessage::Loaded(grid, _) => Loaded(grid),
+            IOMessage::PaletteLoaded(palette) => SetPalette(palette),
             _=> Ignore,
         }
     }
@@ -65,7 +89,8 @@ impl From<IOMessage> for GridServiceMessage<Color> {
 impl From<GridServiceMessage<Color>> for IOMessage {
     fn from(msg: GridServiceMessage<Color>) -> Self {
         match msg {
-            GridServiceMessage::Updated(grid) => IOMessage::GridUpdated(grid),
+            GridServiceMessage::Updated(grid) |
+            GridServiceMessage::Loaded(grid) => IOMessage::GridUpdated(grid),
             _ => IOMessage::Ignore,
         }
     }
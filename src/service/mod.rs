@@ -1,10 +1,12 @@
 use crate::model::*;
+use std::sync::Arc;
 
 mod io;
 mod message;
 mod grid;
 
 use grid::{Service as GridService, Message as GridServiceMessage};
+pub use grid::History;
 use io::{Service as IOService, Message as IOMessage};
 use crate::message::Message;
 
@@ -22,13 +24,20 @@ pub struct AppService {
 impl AppService {
     pub fn new(model: Model<Color>) -> Self {
         Self {
+            io: IOService::new(Arc::new(model.clone())),
             grid: GridService::new(model),
-            io: Default::default(),
         }
     }
-    fn process_with_result(&mut self, msg: Message) -> Result<Option<Message>, String> {
+    fn process_with_result(&mut self, msg: Message) -> Result<Vec<Message>, String> {
         let grid_msg;
+        let mut view = None;
+        let mut catalog = None;
         if let Some(io_response) = self.io.service(msg.clone().into())? {
+            match &io_response {
+                IOMessage::Loaded(_, loaded_view) => view = Some(*loaded_view),
+                IOMessage::CatalogLoaded(loaded) => catalog = Some(loaded.clone()),
+                _ => {},
+            }
             grid_msg = io_response.into();
         } else {
             grid_msg = msg.into();
@@ -37,10 +46,16 @@ impl AppService {
         if let Some(msg) = grid_response.clone() {
             self.io.service(msg.into())?;
         }
-        Ok(grid_response.map(From::from))
+        let mut result: Vec<Message> = grid_response.into_iter().map(From::from).collect();
+        if !result.is_empty() {
+            result.push(Message::HistoryUpdated(Arc::new(self.grid.history())));
+        }
+        result.extend(view.map(Message::ViewLoaded));
+        result.extend(catalog.map(Message::CatalogLoaded));
+        Ok(result)
     }
 
-    pub fn process(&mut self, msg: Message) -> Option<Message> {
-        self.process_with_result(msg).unwrap_or_else(|e|Some(Message::Error(e)))
+    pub fn process(&mut self, msg: Message) -> Vec<Message> {
+        self.process_with_result(msg).unwrap_or_else(|e|vec![Message::Error(e)])
     }
 }
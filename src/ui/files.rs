@@ -4,16 +4,35 @@ use iced::{Element, Svg};
 use std::path::{Path, PathBuf};
 use std::ffi::OsString;
 use std::io;
-use crate::ui::style::FSMenuItem;
+use crate::ui::style::{FSMenuItem, ToggledOn};
 use crate::ui::icon;
+use crate::io::{ChartOptions, ExportOptions, FileType, ImageOptions, Quantization, TextOptions, MAX_BEAD_SIZE, MIN_BEAD_SIZE};
+use crate::model::{Color, Size};
+use std::num::NonZeroUsize;
 
 #[derive(Debug, Clone)]
 pub enum Message {
     DirClicked(usize),
     FileClicked(usize),
     Input(String),
-    Open(PathBuf),
-    Save(PathBuf),
+    /// pattern file and the color of blank CSV cells
+    Open(PathBuf, Color),
+    InputBackground(String),
+    OpenCatalog(PathBuf),
+    OpenPalette(PathBuf),
+    Save(PathBuf, FileType, ExportOptions),
+    SetFileType(FileType),
+    InputBeadSize(String),
+    ChartMarkers(bool),
+    ChartRowNumbers(bool),
+    InputBlockLength(String),
+    UnfinishedOnly(bool),
+    Import(PathBuf, ImageOptions),
+    InputImportWidth(String),
+    InputImportHeight(String),
+    InputImportColors(String),
+    KeepAspect(bool),
+    UsePalette(bool),
 }
 
 struct Files {
@@ -101,15 +120,53 @@ impl FSMenu {
         }
     }
     pub fn open<T: AsRef<Path>>(path: T) -> impl AppWidget<Message=Message> {
+        OpenPatternDialog {
+            btn_completed: Default::default(),
+            input_background: Default::default(),
+            background: Color::default().to_string(),
+            fs_menu: Self::new(path),
+        }
+    }
+    pub fn open_catalog<T: AsRef<Path>>(path: T) -> impl AppWidget<Message=Message> {
         OpenDialog {
             btn_completed: Default::default(),
+            on_open: Message::OpenCatalog,
+            fs_menu: Self::new(path),
+        }
+    }
+    pub fn open_palette<T: AsRef<Path>>(path: T) -> impl AppWidget<Message=Message> {
+        OpenDialog {
+            btn_completed: Default::default(),
+            on_open: Message::OpenPalette,
             fs_menu: Self::new(path),
         }
-
     }
     pub fn save<T: AsRef<Path>>(path: T) -> impl AppWidget<Message=Message> {
         SaveDialog {
             btn_completed: Default::default(),
+            type_buttons: Default::default(),
+            file_type: Default::default(),
+            input_bead_size: Default::default(),
+            bead_size: ChartOptions::default().bead_size.to_string(),
+            chart: Default::default(),
+            input_block: Default::default(),
+            block: TextOptions::default().block.to_string(),
+            text: Default::default(),
+            fs_menu: Self::new(path),
+        }
+    }
+
+    pub fn import<T: AsRef<Path>>(path: T, size: Size) -> impl AppWidget<Message=Message> {
+        ImportDialog {
+            btn_completed: Default::default(),
+            input_width: Default::default(),
+            input_height: Default::default(),
+            input_colors: Default::default(),
+            width: size.width.to_string(),
+            height: size.height.to_string(),
+            colors: "8".to_string(),
+            keep_aspect: true,
+            use_palette: false,
             fs_menu: Self::new(path),
         }
     }
@@ -155,8 +212,19 @@ impl FSMenu {
                 self.selected = None;
                 self.text = text;
             },
-            Message::Open(_) => {/*need to process in caller*/},
-            Message::Save(_) => {/*need to process in caller*/},
+            Message::SetFileType(file_type) => {
+                let path = PathBuf::from(&self.text).with_extension(file_type.extension());
+                self.text = path.to_string_lossy().into_owned();
+                self.selected = self.selected.take().map(|path|path.with_extension(file_type.extension()));
+            },
+            Message::Open(..) | Message::OpenCatalog(_) | Message::OpenPalette(_) => {/*need to process in caller*/},
+            Message::InputBackground(_) => {/*option of the open dialog*/},
+            Message::Save(..) => {/*need to process in caller*/},
+            Message::Import(..) => {/*need to process in caller*/},
+            Message::InputImportWidth(_) | Message::InputImportHeight(_) | Message::InputImportColors(_) |
+            Message::KeepAspect(_) | Message::UsePalette(_) => {/*options of the import dialog*/},
+            Message::InputBeadSize(_) | Message::ChartMarkers(_) | Message::ChartRowNumbers(_) |
+            Message::InputBlockLength(_) | Message::UnfinishedOnly(_) => {/*options of the save dialog*/},
         };
         Ok(())
     }
@@ -185,12 +253,14 @@ impl FSMenu {
     }
 
     fn update(&mut self, msg: Message) {
-        self.update_with_err(msg);
+        // a click on an entry that is gone keeps the menu as it was
+        let _ = self.update_with_err(msg);
     }
 }
 
 pub struct OpenDialog {
     btn_completed: button::State,
+    on_open: fn(PathBuf) -> Message,
     fs_menu: FSMenu,
 }
 
@@ -201,7 +271,7 @@ impl AppWidget for OpenDialog {
     fn view(&mut self) -> Element<'_, Self::Message> {
         let mut btn = Button::new(&mut self.btn_completed, icon::OPEN.svg());
         if let Some(selected) = self.fs_menu.selected() {
-            btn = btn.on_press(Message::Open(selected));
+            btn = btn.on_press((self.on_open)(selected));
         }
         self.fs_menu.view_with_btn(btn)
     }
@@ -212,24 +282,211 @@ impl AppWidget for OpenDialog {
 }
 
 
+/// Opens patterns, blank cells of CSV grids get the chosen color
+pub struct OpenPatternDialog {
+    btn_completed: button::State,
+    input_background: text_input::State,
+    background: String,
+    fs_menu: FSMenu,
+}
+
+impl AppWidget for OpenPatternDialog {
+    type Message = Message;
+
+    fn view(&mut self) -> Element<'_, Self::Message> {
+        let mut btn = Button::new(&mut self.btn_completed, icon::OPEN.svg());
+        if let (Some(selected), Ok(background)) = (self.fs_menu.selected(), self.background.parse()) {
+            btn = btn.on_press(Message::Open(selected, background));
+        }
+        let options = Row::new().spacing(5)
+            .push(Text::new("Blank CSV cells: ").size(15))
+            .push(TextInput::new(&mut self.input_background, "#FFFFFF", &self.background, Message::InputBackground)
+                .size(15).width(Length::Units(70)));
+        Column::new()
+            .push(options)
+            .push(self.fs_menu.view_with_btn(btn))
+            .into()
+    }
+
+    fn update(&mut self, msg: Self::Message) {
+        if let Message::InputBackground(s) = &msg {
+            self.background = s.clone();
+        }
+        self.fs_menu.update(msg)
+    }
+}
+
+
 pub struct SaveDialog {
     btn_completed: button::State,
+    type_buttons: [button::State; 10],
+    file_type: FileType,
+    input_bead_size: text_input::State,
+    bead_size: String,
+    chart: ChartOptions,
+    input_block: text_input::State,
+    block: String,
+    text: TextOptions,
     fs_menu: FSMenu,
 }
 
+impl SaveDialog {
+    /// Only the fields shown for the chosen file type are checked
+    fn options(&self) -> Option<ExportOptions> {
+        let mut options = ExportOptions {chart: self.chart, text: self.text};
+        match self.file_type {
+            FileType::Chart => {
+                options.chart.bead_size = self.bead_size.parse().ok()
+                    .filter(|size|(MIN_BEAD_SIZE..=MAX_BEAD_SIZE).contains(size))?;
+            },
+            FileType::LineText => {
+                options.text.block = self.block.parse().ok().filter(|&block|block > 0)?;
+            },
+            _ => {},
+        }
+        Some(options)
+    }
+}
+
 
 impl AppWidget for SaveDialog {
     type Message = Message;
 
     fn view(&mut self) -> Element<'_, Self::Message> {
+        let options = self.options();
         let mut btn = Button::new(&mut self.btn_completed, icon::SAVE.svg());
-        if let Some(selected) = self.fs_menu.selected() {
-            btn = btn.on_press(Message::Save(selected));
+        if let (Some(selected), Some(options)) = (self.fs_menu.selected(), options) {
+            btn = btn.on_press(Message::Save(selected, self.file_type, options));
         }
-        self.fs_menu.view_with_btn(btn)
+        let active = self.file_type;
+        let types = self.type_buttons.iter_mut().zip(FileType::ALL.iter())
+            .map(|(state, &file_type)| {
+                let btn = Button::new(state, Text::new(file_type.name()).size(15))
+                    .on_press(Message::SetFileType(file_type));
+                let btn = if file_type == active { btn.style(ToggledOn) } else { btn };
+                btn.into()
+            })
+            .collect::<Vec<_>>();
+        let mut options = Column::new().spacing(2);
+        let mut types = types.into_iter().peekable();
+        while types.peek().is_some() {
+            options = options.push(Row::with_children(types.by_ref().take(4).collect()).spacing(2));
+        }
+        if self.file_type == FileType::LineText {
+            options = options
+                .push(Row::new().spacing(5)
+                    .push(Text::new("Block: ").size(15).width(Length::Units(80)))
+                    .push(input(&mut self.input_block, &self.block, Message::InputBlockLength))
+                )
+                .push(Checkbox::new(self.text.unfinished_only, "Only unfinished", Message::UnfinishedOnly).text_size(15));
+        }
+        if self.file_type == FileType::Chart {
+            options = options
+                .push(Row::new().spacing(5)
+                    .push(Text::new("Bead size: ").size(15).width(Length::Units(80)))
+                    .push(input(&mut self.input_bead_size, &self.bead_size, Message::InputBeadSize))
+                )
+                .push(Checkbox::new(self.chart.markers, "Line start markers", Message::ChartMarkers).text_size(15))
+                .push(Checkbox::new(self.chart.row_numbers, "Row numbers", Message::ChartRowNumbers).text_size(15));
+        }
+        Column::new()
+            .push(options)
+            .push(self.fs_menu.view_with_btn(btn))
+            .into()
     }
 
     fn update(&mut self, msg: Self::Message) {
+        match &msg {
+            Message::SetFileType(file_type) => self.file_type = *file_type,
+            Message::InputBeadSize(s) => self.bead_size = s.clone(),
+            Message::ChartMarkers(markers) => self.chart.markers = *markers,
+            Message::ChartRowNumbers(numbers) => self.chart.row_numbers = *numbers,
+            Message::InputBlockLength(s) => self.block = s.clone(),
+            Message::UnfinishedOnly(unfinished) => self.text.unfinished_only = *unfinished,
+            _ => {},
+        }
         self.fs_menu.update(msg)
     }
-}
\ No newline at end of file
+}
+
+
+pub struct ImportDialog {
+    btn_completed: button::State,
+    input_width: text_input::State,
+    input_height: text_input::State,
+    input_colors: text_input::State,
+    width: String,
+    height: String,
+    colors: String,
+    keep_aspect: bool,
+    use_palette: bool,
+    fs_menu: FSMenu,
+}
+
+impl ImportDialog {
+    fn options(&self) -> Option<ImageOptions> {
+        let width = NonZeroUsize::new(self.width.parse().ok()?)?;
+        let height = NonZeroUsize::new(self.height.parse().ok()?)?;
+        let quantization = if self.use_palette {
+            Quantization::Palette
+        } else {
+            Quantization::Colors(self.colors.parse().ok().filter(|&colors|colors > 0)?)
+        };
+        Some(ImageOptions {
+            size: Size {width, height},
+            keep_aspect: self.keep_aspect,
+            quantization,
+        })
+    }
+}
+
+fn input<'a>(state: &'a mut text_input::State, value: &str, on_change: fn(String) -> Message) -> TextInput<'a, Message> {
+    TextInput::new(state, "", value, on_change).size(15).width(Length::Units(50))
+}
+
+impl AppWidget for ImportDialog {
+    type Message = Message;
+
+    fn view(&mut self) -> Element<'_, Self::Message> {
+        let options = self.options();
+        let mut btn = Button::new(&mut self.btn_completed, icon::OPEN.svg());
+        if let (Some(selected), Some(options)) = (self.fs_menu.selected(), options) {
+            btn = btn.on_press(Message::Import(selected, options));
+        }
+        let mut height = Row::new().spacing(5)
+            .push(Text::new("Height: ").size(15).width(Length::Units(60)));
+        if !self.keep_aspect {
+            height = height.push(input(&mut self.input_height, &self.height, Message::InputImportHeight));
+        }
+        let mut colors = Row::new().spacing(5)
+            .push(Text::new("Colors: ").size(15).width(Length::Units(60)));
+        if !self.use_palette {
+            colors = colors.push(input(&mut self.input_colors, &self.colors, Message::InputImportColors));
+        }
+        let options = Column::new().spacing(2)
+            .push(Row::new().spacing(5)
+                .push(Text::new("Width: ").size(15).width(Length::Units(60)))
+                .push(input(&mut self.input_width, &self.width, Message::InputImportWidth))
+            )
+            .push(Checkbox::new(self.keep_aspect, "Keep proportions", Message::KeepAspect).text_size(15))
+            .push(height)
+            .push(Checkbox::new(self.use_palette, "Current palette", Message::UsePalette).text_size(15))
+            .push(colors);
+        Column::new()
+            .push(options)
+            .push(self.fs_menu.view_with_btn(btn))
+            .into()
+    }
+
+    fn update(&mut self, msg: Self::Message) {
+        match &msg {
+            Message::InputImportWidth(s) => self.width = s.clone(),
+            Message::InputImportHeight(s) => self.height = s.clone(),
+            Message::InputImportColors(s) => self.colors = s.clone(),
+            Message::KeepAspect(keep) => self.keep_aspect = *keep,
+            Message::UsePalette(use_palette) => self.use_palette = *use_palette,
+            _ => {},
+        }
+        self.fs_menu.update(msg)
+    }
+}
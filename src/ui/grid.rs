@@ -1,8 +1,10 @@
 use crate::reimport::*;
-use super::AppWidget;
+use super::{AppWidget, icon};
 use super::widget::ColorBox;
 use std::{sync::Arc, iter};
+use std::collections::{HashSet, HashMap};
 use crate::model::*;
+use crate::model::grid::SimplifiedGrid;
 use std::fmt::Debug;
 
 
@@ -11,17 +13,85 @@ pub enum Message<T: Debug + Send + Sync> {
     Ignore,
     Press(Coord),
     Move(Coord),
+    Fill(Coord),
+    ShapeStart(Coord),
+    ShapeMove(Coord),
+    DrawShape(Shape, Coord, Coord),
+    SelectStart(Coord),
+    SelectMove(Coord),
+    Copy,
+    Cut(Vec<(Coord, Color)>),
+    StartPaste,
+    StartMove,
+    FloatMove(Coord),
+    Paste(Vec<(Coord, Color)>),
+    CancelPaste,
+    TransformArea(Transform, Coord, Coord),
+    SetTool(Tool),
+    /// the color of the pressed bead is activated
+    Pick(Color),
+    /// the eyedropper works with any tool while the modifier is held
+    SetPicking(bool),
     GridUpdated(Arc<T>),
     Rotate(isize),
     SetRotation(isize),
+    SetView(ViewState),
     ZoomIn,
     ZoomOut,
     MouseRelease,
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum Tool {
+    #[default]
+    Pencil,
+    Fill,
+    Shape(Shape),
+    Select,
+    Eyedropper,
+}
+
+/// Copied beads following the mouse until they are placed
+struct Floating {
+    data: SimplifiedGrid<Bead<Color>>,
+    clear: Vec<Coord>,
+    at: Option<Coord>,
+}
+
+impl Floating {
+    fn items(&self, size: Size) -> Vec<(Coord, Color)> {
+        let clear = self.clear.iter().map(|&coord|(coord, Color::default()));
+        match self.at {
+            Some(at) => clear.chain(
+                self.data.placed(at, size).into_iter().map(|(coord, Bead {color, ..})|(coord, color))
+            ).collect(),
+            None => clear.collect(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct SelectionButtons {
+    copy: button::State,
+    cut: button::State,
+    paste: button::State,
+    move_btn: button::State,
+    cancel: button::State,
+    mirror: button::State,
+    flip: button::State,
+    turn: button::State,
+}
+
 pub struct GridPlate<T> {
     grid_ref: Arc<T>,
+    tool: Tool,
+    shape: Option<(Coord, Coord)>,
+    selection: Option<(Coord, Coord)>,
+    clipboard: Option<SimplifiedGrid<Bead<Color>>>,
+    floating: Option<Floating>,
+    selection_buttons: SelectionButtons,
     mouse_hold: bool,
+    picking: bool,
     rotation: isize,
     scroll: scrollable::State,
     slider: slider::State,
@@ -34,15 +104,71 @@ impl<T> GridPlate<T> {
     pub fn new(grid_ref: Arc<T>) -> Self {
         Self {
             grid_ref,
+            tool: Default::default(),
+            shape: None,
+            selection: None,
+            clipboard: None,
+            floating: None,
+            selection_buttons: Default::default(),
             mouse_hold: false,
-            rotation: 0,
-            half_size: 6,
+            picking: false,
+            rotation: ViewState::default().rotation,
+            half_size: ViewState::default().zoom,
             slider: Default::default(),
             scroll: Default::default(),
             rot_l: Default::default(),
             rot_r: Default::default(),
         }
     }
+    pub fn view_state(&self) -> ViewState {
+        ViewState {
+            rotation: self.rotation,
+            zoom: self.half_size,
+        }
+    }
+}
+
+impl SelectionButtons {
+    fn view<'a, T: 'a + Debug + Send + Sync + Clone>(
+        &'a mut self,
+        selection: Option<(Coord, Coord)>,
+        copied: bool,
+        floating: bool,
+        cut_items: Vec<(Coord, Color)>,
+    ) -> Element<'a, Message<T>> {
+        let mut copy = Button::new(&mut self.copy, Text::new("Copy"));
+        let mut cut = Button::new(&mut self.cut, Text::new("Cut"));
+        let mut move_btn = Button::new(&mut self.move_btn, Text::new("Move"));
+        let mut paste = Button::new(&mut self.paste, Text::new("Paste"));
+        let mut cancel = Button::new(&mut self.cancel, Text::new("Cancel"));
+        let mut mirror = Button::new(&mut self.mirror, icon::MIRROR.svg());
+        let mut flip = Button::new(&mut self.flip, icon::FLIP.svg());
+        let mut turn = Button::new(&mut self.turn, icon::TURN.svg());
+        if let (Some((from, to)), false) = (selection, floating) {
+            copy = copy.on_press(Message::Copy);
+            cut = cut.on_press(Message::Cut(cut_items));
+            move_btn = move_btn.on_press(Message::StartMove);
+            mirror = mirror.on_press(Message::TransformArea(Transform::Mirror, from, to));
+            flip = flip.on_press(Message::TransformArea(Transform::Flip, from, to));
+            turn = turn.on_press(Message::TransformArea(Transform::Turn, from, to));
+        }
+        if copied && !floating {
+            paste = paste.on_press(Message::StartPaste);
+        }
+        if floating {
+            cancel = cancel.on_press(Message::CancelPaste);
+        }
+        Row::new().spacing(5)
+            .push(copy)
+            .push(cut)
+            .push(move_btn)
+            .push(paste)
+            .push(cancel)
+            .push(mirror.width(Length::Units(30)))
+            .push(flip.width(Length::Units(30)))
+            .push(turn.width(Length::Units(30)))
+            .into()
+    }
 }
 
 fn normalize_rotation(rot: isize, width: usize) -> usize {
@@ -51,13 +177,15 @@ fn normalize_rotation(rot: isize, width: usize) -> usize {
     if modulo >= 0 { modulo as usize} else { (width + modulo) as usize }
 }
 
-impl<T: AsRef<BeadGrid> + Debug + Send + Sync + Clone + GetSchema> AppWidget for GridPlate<T> {
+impl<T> AppWidget for GridPlate<T>
+where T: AsRef<BeadGrid> + AsRef<Palette<Color>> + Debug + Send + Sync + Clone + GetSchema {
     type Message = Message<T>;
 
     fn view(&mut self) -> Element<'_, Message<T>> {
         let full = Length::Units(self.half_size * 2);
         let half = Length::Units(self.half_size);
-        let grid = self.grid_ref.as_ref().as_ref();
+        let grid = AsRef::<BeadGrid>::as_ref(self.grid_ref.as_ref());
+        let active = AsRef::<Palette<Color>>::as_ref(self.grid_ref.as_ref()).activated();
         let schema = self.grid_ref.get_schema();
         let portions = match schema {
             Schema::FirstOffset => [full, half, full],
@@ -67,6 +195,24 @@ impl<T: AsRef<BeadGrid> + Debug + Send + Sync + Clone + GetSchema> AppWidget for
         let width = grid.width();
         let range = 0..width;
         let rotation = normalize_rotation(self.rotation, width);
+        let size = grid.size();
+        let mut preview: HashMap<_, _> = match (self.tool, self.shape) {
+            (Tool::Shape(shape), Some((from, to))) => shape.coords(from, to).into_iter()
+                .map(|Coord {x, y}|(Coord {x, y: y % width}, *active))
+                .collect(),
+            _ => HashMap::new(),
+        };
+        let paste_items = self.floating.as_ref().map(|floating|floating.items(size));
+        if let Some(items) = &paste_items {
+            preview.extend(items.iter().cloned());
+        }
+        let paste_at = self.floating.as_ref()
+            .and_then(|floating|floating.at)
+            .map(|Coord {x, y}|Coord {x, y: y % width});
+        let selected: HashSet<_> = self.selection.iter()
+            .flat_map(|&(from, to)|Shape::FilledRectangle.coords(from, to))
+            .map(|Coord {x, y}|Coord {x, y: y % width})
+            .collect();
         let grid = Column::with_children(
             grid.as_full_table_iter().enumerate().map(|(index, row)| {
                 let portion_index = index % 2;
@@ -77,14 +223,37 @@ impl<T: AsRef<BeadGrid> + Debug + Send + Sync + Clone + GetSchema> AppWidget for
                     .zip(range.clone().into_iter().cycle())
                     .skip(rotation)
                     .take(width)
-                    .map(|((Bead {color, filled}, first), col)| {
+                    .enumerate()
+                    .map(|(position, ((Bead {color, filled}, first), col))| {
                         let coord = Coord{x:index, y:col};
+                        // column keeps growing through the seam, so shapes can cross it
+                        let shape_coord = Coord{x:index, y: position + rotation};
+                        let bead_color = color;
+                        let color = preview.get(&coord).unwrap_or(color);
+                        let press = match (self.tool, &paste_items) {
+                            (_, Some(items)) if paste_at == Some(coord) => Message::Paste(items.clone()),
+                            (_, Some(_)) => Message::FloatMove(shape_coord),
+                            (Tool::Eyedropper, _) => Message::Pick(*bead_color),
+                            _ if self.picking => Message::Pick(*bead_color),
+                            (Tool::Pencil, _) => Message::Press(coord),
+                            (Tool::Fill, _) => Message::Fill(coord),
+                            (Tool::Shape(_), _) => Message::ShapeStart(shape_coord),
+                            (Tool::Select, _) => Message::SelectStart(shape_coord),
+                        };
                         let mut widget = ColorBox::new(color.clone())
                             .width(full)
                             .height(full)
-                            .on_press(Message::Press(coord).into());
-                        if self.mouse_hold {
-                            widget = widget.on_over(Message::Move(coord));
+                            .on_press(press);
+                        if paste_items.is_some() {
+                            widget = widget.on_over(Message::FloatMove(shape_coord));
+                        } else if self.mouse_hold {
+                            widget = match (self.tool, self.shape) {
+                                (Tool::Shape(shape), Some((from, _))) => widget
+                                    .on_over(Message::ShapeMove(shape_coord))
+                                    .on_release(Message::DrawShape(shape, from, shape_coord)),
+                                (Tool::Select, _) => widget.on_over(Message::SelectMove(shape_coord)),
+                                _ => widget.on_over(Message::Move(coord)),
+                            };
                         }
                         if *first {
                             widget = widget.border_color(iced::Color::from_rgb(0.9, 0.0, 0.0))
@@ -92,6 +261,9 @@ impl<T: AsRef<BeadGrid> + Debug + Send + Sync + Clone + GetSchema> AppWidget for
                         if *filled {
                             widget = widget.border_color(iced::Color::WHITE);
                         }
+                        if selected.contains(&coord) {
+                            widget = widget.border_color(iced::Color::from_rgb(0.0, 0.0, 0.9));
+                        }
                         widget.into()
                     })
                 ).chain( //right cell
@@ -104,6 +276,17 @@ impl<T: AsRef<BeadGrid> + Debug + Send + Sync + Clone + GetSchema> AppWidget for
             .height(Length::Fill)
             .align_y(Align::Center)
             .align_x(Align::Center);
+        let cut_items: Vec<_> = selected.iter().map(|&coord|(coord, Color::default())).collect();
+        let selection_row = if self.tool == Tool::Select {
+            Some(self.selection_buttons.view(
+                self.selection,
+                self.clipboard.is_some(),
+                self.floating.is_some(),
+                cut_items,
+            ))
+        } else {
+            None
+        };
         let width = width as i32;
         let slider = Element::new(Slider::new(
             &mut self.slider,
@@ -111,7 +294,11 @@ impl<T: AsRef<BeadGrid> + Debug + Send + Sync + Clone + GetSchema> AppWidget for
             self.rotation as i32,
             |v|{Message::SetRotation(v as isize)}
         ).width(Length::FillPortion(8)));
-        Column::new().push(grid).push(Row::new()
+        let mut column = Column::new().push(grid);
+        if let Some(row) = selection_row {
+            column = column.push(row);
+        }
+        column.push(Row::new()
             .push(Container::new(
                 Button::new(&mut self.rot_l, Text::new("<")).on_press(Message::Rotate(-1))
             ).width(Length::FillPortion(1)).align_x(Align::Start))
@@ -125,14 +312,73 @@ impl<T: AsRef<BeadGrid> + Debug + Send + Sync + Clone + GetSchema> AppWidget for
     fn update(&mut self, msg: Message<T>) {
         use Message::*;
         match msg {
-            MouseRelease => self.mouse_hold = false,
+            MouseRelease => {
+                self.mouse_hold = false;
+                self.shape = None;
+            },
+            SelectStart(coord) => {
+                self.mouse_hold = true;
+                self.selection = Some((coord, coord));
+            },
+            SelectMove(coord) => if let Some((_, to)) = self.selection.as_mut() {
+                *to = coord;
+            },
+            Copy | Cut(..) => if let Some((from, to)) = self.selection {
+                let grid = AsRef::<BeadGrid>::as_ref(self.grid_ref.as_ref());
+                self.clipboard = Some(grid.area(from, to));
+            },
+            StartPaste => {
+                self.floating = self.clipboard.clone().map(|data|Floating {
+                    data,
+                    clear: Vec::new(),
+                    at: None,
+                });
+            },
+            StartMove => if let Some((from, to)) = self.selection {
+                let grid = AsRef::<BeadGrid>::as_ref(self.grid_ref.as_ref());
+                let width = grid.width();
+                self.floating = Some(Floating {
+                    data: grid.area(from, to),
+                    clear: Shape::FilledRectangle.coords(from, to).into_iter()
+                        .map(|Coord {x, y}|Coord {x, y: y % width})
+                        .collect(),
+                    at: None,
+                });
+            },
+            FloatMove(coord) => if let Some(floating) = self.floating.as_mut() {
+                floating.at = Some(coord);
+            },
+            Paste(..) => {
+                self.floating = None;
+                self.selection = None;
+            },
+            CancelPaste => self.floating = None,
             GridUpdated(model) => self.grid_ref = model,
             Rotate(rotation) => { self.rotation += rotation; }
             SetRotation(rotation) => {self.rotation = rotation; }
+            SetView(ViewState {rotation, zoom}) => {
+                self.rotation = rotation;
+                self.half_size = zoom.max(1);
+            },
             ZoomIn => { self.half_size += 1; }
             ZoomOut => if self.half_size > 1 { self.half_size -= 1; },
+            SetTool(tool) => {
+                self.tool = tool;
+                self.shape = None;
+                self.selection = None;
+                self.floating = None;
+            },
             Press(..) => self.mouse_hold = true,
-            Move(..) | Ignore => {}
+            ShapeStart(coord) => {
+                self.mouse_hold = true;
+                self.shape = Some((coord, coord));
+            },
+            ShapeMove(coord) => if let Some((_, to)) = self.shape.as_mut() {
+                *to = coord;
+            },
+            DrawShape(..) => self.shape = None,
+            SetPicking(picking) => self.picking = picking,
+            Move(..) | Fill(..) | TransformArea(..) | Pick(..) | Ignore => {}
         }
     }
 }
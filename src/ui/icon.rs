@@ -1,5 +1,7 @@
 pub const BEADS_LINE: SvgData = SvgData(include_bytes!("../../resources/beads-line-icon.svg"));
 pub const CONFIG_COLOR: SvgData = SvgData(include_bytes!("../../resources/color-config.svg"));
+pub const HISTORY: SvgData = SvgData(include_bytes!("../../resources/history.svg"));
+pub const CATALOG: SvgData = SvgData(include_bytes!("../../resources/catalog.svg"));
 
 pub const ADD_LEFT_COLUMN: SvgData = SvgData(include_bytes!("../../resources/add-left-column.svg"));
 pub const ADD_RIGHT_COLUMN: SvgData = SvgData(include_bytes!("../../resources/add-right-column.svg"));
@@ -24,8 +26,22 @@ pub const CHANGE_SCHEMA: SvgData = SvgData(include_bytes!("../../resources/chang
 pub const SEAM_LEFT: SvgData = SvgData(include_bytes!("../../resources/seam-to-left.svg"));
 pub const SEAM_RIGHT: SvgData = SvgData(include_bytes!("../../resources/seam-to-right.svg"));
 
+pub const MIRROR: SvgData = SvgData(include_bytes!("../../resources/mirror.svg"));
+pub const FLIP: SvgData = SvgData(include_bytes!("../../resources/flip.svg"));
+pub const TURN: SvgData = SvgData(include_bytes!("../../resources/turn.svg"));
+
+pub const PENCIL: SvgData = SvgData(include_bytes!("../../resources/pencil.svg"));
+pub const FILL: SvgData = SvgData(include_bytes!("../../resources/fill.svg"));
+pub const LINE: SvgData = SvgData(include_bytes!("../../resources/line.svg"));
+pub const RECTANGLE: SvgData = SvgData(include_bytes!("../../resources/rectangle.svg"));
+pub const FILLED_RECTANGLE: SvgData = SvgData(include_bytes!("../../resources/filled-rectangle.svg"));
+pub const ELLIPSE: SvgData = SvgData(include_bytes!("../../resources/ellipse.svg"));
+pub const SELECT: SvgData = SvgData(include_bytes!("../../resources/select.svg"));
+pub const EYEDROPPER: SvgData = SvgData(include_bytes!("../../resources/eyedropper.svg"));
+
 pub const SAVE: SvgData = SvgData(include_bytes!("../../resources/save.svg"));
 pub const OPEN: SvgData = SvgData(include_bytes!("../../resources/open.svg"));
+pub const IMPORT: SvgData = SvgData(include_bytes!("../../resources/import.svg"));
 pub const FOLDER: SvgData = SvgData(include_bytes!("../../resources/folder.svg"));
 pub const FILE: SvgData = SvgData(include_bytes!("../../resources/file.svg"));
 
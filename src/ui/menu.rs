@@ -1,8 +1,9 @@
 use crate::reimport::*;
 use super::{AppWidget, icon, palette};
+use super::grid::Tool;
 use super::style::ToggledOn;
 use super::SvgButton;
-use crate::model::{Color, Model};
+use crate::model::{Color, Model, Shape, Transform};
 use std::sync::Arc;
 
 pub mod top {
@@ -13,6 +14,7 @@ pub mod top {
         palette: Palette,
         save: SvgButton,
         load: SvgButton,
+        import: SvgButton,
         undo: SvgButton,
         redo: SvgButton,
 
@@ -25,6 +27,7 @@ pub mod top {
                 palette: Palette::new(model),
                 save: SvgButton::new(icon::SAVE),
                 load: SvgButton::new(icon::OPEN),
+                import: SvgButton::new(icon::IMPORT),
                 undo: SvgButton::new(icon::UNDO),
                 redo: SvgButton::new(icon::REDO),
                 active_mode: Default::default(),
@@ -37,6 +40,7 @@ pub mod top {
         Empty,
         Save,
         Open,
+        Import,
     }
 
     impl Default for ActiveMode {
@@ -51,6 +55,7 @@ pub mod top {
         Hide,
         Open,
         Save,
+        Import,
         Palette(palette::Message),
         Undo,
         Redo,
@@ -62,14 +67,17 @@ pub mod top {
         fn view(&mut self) -> Element<'_, Message> {
             let mut btn_load = self.load.button().on_press(Message::Open);
             let mut btn_save = self.save.button().on_press(Message::Save);
+            let mut btn_import = self.import.button().on_press(Message::Import);
             match self.active_mode {
                 ActiveMode::Empty => {},
                 ActiveMode::Save => {btn_save = btn_save.on_press(Message::Hide).style(ToggledOn)},
                 ActiveMode::Open => {btn_load = btn_load.on_press(Message::Hide).style(ToggledOn)},
+                ActiveMode::Import => {btn_import = btn_import.on_press(Message::Hide).style(ToggledOn)},
             }
             Container::new(Row::new()
                 .push(btn_load)
                 .push(btn_save)
+                .push(btn_import)
                 .push(
                     self.undo.button().on_press(Message::Undo)
                 )
@@ -86,6 +94,7 @@ pub mod top {
                 Message::Hide => self.active_mode = ActiveMode::Empty,
                 Message::Open => self.active_mode = ActiveMode::Open,
                 Message::Save => self.active_mode = ActiveMode::Save,
+                Message::Import => self.active_mode = ActiveMode::Import,
                 _ => {}
             }
         }
@@ -104,6 +113,8 @@ pub mod right {
     enum Activated {
         Beads,
         Colors,
+        History,
+        Catalog,
         None,
     }
 
@@ -117,6 +128,8 @@ pub mod right {
     pub struct RightMenu {
         beads_btn: button::State,
         colors_btn: button::State,
+        history_btn: button::State,
+        catalog_btn: button::State,
         activated: Activated,
     }
 
@@ -126,6 +139,8 @@ pub mod right {
         Ignore,
         ShowBeads,
         ShowColors,
+        ShowHistory,
+        ShowCatalog,
         Hide,
     }
 
@@ -136,13 +151,20 @@ pub mod right {
                 .on_press(Message::ShowBeads);
             let mut colors_btn = Button::new(&mut self.colors_btn, icon::CONFIG_COLOR.svg())
                 .on_press(Message::ShowColors);
+            let mut history_btn = Button::new(&mut self.history_btn, icon::HISTORY.svg())
+                .on_press(Message::ShowHistory);
+            let mut catalog_btn = Button::new(&mut self.catalog_btn, icon::CATALOG.svg())
+                .on_press(Message::ShowCatalog);
             use Activated::*;
             match self.activated {
                 Beads => beads_btn = beads_btn.on_press(Message::Hide).style(ToggledOn),
                 Colors => colors_btn = colors_btn.on_press(Message::Hide).style(ToggledOn),
+                History => history_btn = history_btn.on_press(Message::Hide).style(ToggledOn),
+                Catalog => catalog_btn = catalog_btn.on_press(Message::Hide).style(ToggledOn),
                 None => {},
             }
-            let buttons = Column::new().width(Length::Fill).push(beads_btn ).push(colors_btn);
+            let buttons = Column::new().width(Length::Fill).push(beads_btn ).push(colors_btn).push(history_btn)
+                .push(catalog_btn);
             Container::new(buttons).into()
         }
 
@@ -150,6 +172,8 @@ pub mod right {
             match msg {
                 Message::ShowBeads => self.activated = Activated::Beads,
                 Message::ShowColors => self.activated = Activated::Colors,
+                Message::ShowHistory => self.activated = Activated::History,
+                Message::ShowCatalog => self.activated = Activated::Catalog,
                 Message::Hide => self.activated = Activated::None,
                 Message::Ignore => {}
             }
@@ -169,6 +193,8 @@ pub mod left {
         ZoomIn,
         ZoomOut,
         MoveSeam(isize),
+        Transform(Transform),
+        SetTool(Tool),
     }
 
     #[derive(PartialEq, Clone, Copy)]
@@ -191,6 +217,18 @@ pub mod left {
         schema_change: SvgButton,
         seam_left: SvgButton,
         seam_right: SvgButton,
+        mirror: SvgButton,
+        flip: SvgButton,
+        turn: SvgButton,
+        tool: Tool,
+        pencil: SvgButton,
+        fill: SvgButton,
+        line: SvgButton,
+        rectangle: SvgButton,
+        filled_rectangle: SvgButton,
+        ellipse: SvgButton,
+        select: SvgButton,
+        eyedropper: SvgButton,
     }
     
     impl Default for Menu {
@@ -204,10 +242,27 @@ pub mod left {
                 schema_change: SvgButton::new(CHANGE_SCHEMA),
                 seam_left: SvgButton::new(SEAM_LEFT),
                 seam_right: SvgButton::new(SEAM_RIGHT),
+                mirror: SvgButton::new(MIRROR),
+                flip: SvgButton::new(FLIP),
+                turn: SvgButton::new(TURN),
+                tool: Default::default(),
+                pencil: SvgButton::new(PENCIL),
+                fill: SvgButton::new(FILL),
+                line: SvgButton::new(LINE),
+                rectangle: SvgButton::new(RECTANGLE),
+                filled_rectangle: SvgButton::new(FILLED_RECTANGLE),
+                ellipse: SvgButton::new(ELLIPSE),
+                select: SvgButton::new(SELECT),
+                eyedropper: SvgButton::new(EYEDROPPER),
             }
         }
     }
 
+    fn tool_button(btn: &mut SvgButton, tool: Tool, active: Tool) -> Button<'_, Message> {
+        let btn = btn.button().on_press(Message::SetTool(tool));
+        if tool == active { btn.style(ToggledOn) } else { btn }
+    }
+
     impl AppWidget for Menu {
         type Message = Message;
 
@@ -220,13 +275,25 @@ pub mod left {
             if toggled_on {
                 resize_btn = resize_btn.style(ToggledOn);
             }
+            let tool = self.tool;
             Column::new().width(Length::Fill).spacing(5)
+                .push(tool_button(&mut self.pencil, Tool::Pencil, tool))
+                .push(tool_button(&mut self.fill, Tool::Fill, tool))
+                .push(tool_button(&mut self.line, Tool::Shape(Shape::Line), tool))
+                .push(tool_button(&mut self.rectangle, Tool::Shape(Shape::Rectangle), tool))
+                .push(tool_button(&mut self.filled_rectangle, Tool::Shape(Shape::FilledRectangle), tool))
+                .push(tool_button(&mut self.ellipse, Tool::Shape(Shape::Ellipse), tool))
+                .push(tool_button(&mut self.select, Tool::Select, tool))
+                .push(tool_button(&mut self.eyedropper, Tool::Eyedropper, tool))
                 .push(resize_btn)
                 .push(self.zoom_in.button().on_press(Message::ZoomIn))
                 .push(self.zoom_out.button().on_press(Message::ZoomOut))
                 .push(self.schema_change.button().on_press(Message::SchemaChange))
                 .push(self.seam_left.button().on_press(Message::MoveSeam(-1)))
                 .push(self.seam_right.button().on_press(Message::MoveSeam(1)))
+                .push(self.mirror.button().on_press(Message::Transform(Transform::Mirror)))
+                .push(self.flip.button().on_press(Message::Transform(Transform::Flip)))
+                .push(self.turn.button().on_press(Message::Transform(Transform::Turn)))
                 .into()
         }
 
@@ -238,6 +305,9 @@ pub mod left {
                 Message::Hide => {
                     self.active = ActiveMode::Empty;
                 }
+                Message::SetTool(tool) => {
+                    self.tool = tool;
+                }
                 _ => {}
             }
         }
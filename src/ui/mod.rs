@@ -16,7 +16,7 @@ pub use panel::right::{Message as RightPanelMessage, RightPanel};
 pub use panel::left::{Message as LeftPanelMessage, Panel as LeftPanel };
 pub use files::Message as FilesMessage;
 pub use grid::{Message as GridMessage, GridPlate};
-pub use widget::MouseListener;
+pub use widget::EventListener;
 
 pub trait AppWidget {
     type Message;
@@ -2,6 +2,7 @@ use crate::model::*;
 use crate::model;
 use crate::reimport::*;
 use super::AppWidget;
+use super::widget::ColorBox;
 use std::sync::Arc;
 
 pub trait AsPalette :  std::fmt::Debug + AsRef<model::Palette<Color>> {}
@@ -11,25 +12,23 @@ type PaletteArc = Arc<dyn AsPalette + Send + Sync>;
 
 pub struct Palette {
     model: PaletteArc,
-    buttons: Vec<button::State>,
+    /// index of the color being dragged to a new place
+    dragged: Option<usize>,
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
     ActivateColor(Color),
+    /// press on the color, it is activated and may be dragged
+    Grab(usize, Color),
+    MoveColor(usize, usize),
+    Release,
     Updated(PaletteArc),
 }
 
-fn create_buttons(palette: &model::Palette<Color>) -> Vec<button::State> {
-    (0..palette.colors().len())
-        .map(|_|button::State::new())
-        .collect()
-}
-
 impl Palette {
     pub fn new(model: PaletteArc) -> Self {
-        let buttons = create_buttons(model.as_ref().as_ref());
-        Self { model, buttons }
+        Self { model, dragged: None }
     }
 }
 
@@ -37,43 +36,40 @@ impl AppWidget for Palette {
     type Message = Message;
 
     fn view(&mut self) -> Element<'_, Message> {
-        let mut sorted: Vec<_> = self.model.as_ref().as_ref().colors().iter().map(|(c,&a)|{
-            (c.clone(), a)
-        }).collect();
-        sorted.sort_unstable_by_key(|(color, ..)|{
-            let Color {r, g, b} = color;
-            (*r as u32) + (*g as u32) + (*b as u32)
-        });
-        let [top, bot] = sorted.into_iter()
-            .zip(self.buttons.iter_mut())
-            .map(|((color, active),btn)| {
-                let space = Space::new(Length::Units(7), Length::Units(5));
-                let mut button = Button::new(
-                    btn,
-                    space,
-                ).style(crate::ui::style::ColorButton(color.clone().into()));
-                if !active {
-                    button = button.on_press(Message::ActivateColor(color.clone()));
+        let palette: &model::Palette<Color> = self.model.as_ref().as_ref();
+        let dragged = self.dragged;
+        let [top, bot] = palette.colors().iter()
+            .enumerate()
+            .map(|(i, &color)| {
+                let border = if &color == palette.activated() { iced::Color::WHITE } else { iced::Color::BLACK };
+                let color_box = ColorBox::new(color)
+                    .width(Length::Units(16))
+                    .height(Length::Units(14))
+                    .border_color(border)
+                    .on_press(Message::Grab(i, color));
+                match dragged {
+                    Some(from) if from != i => color_box.on_release(Message::MoveColor(from, i)),
+                    _ => color_box.on_release(Message::Release),
                 }
-                button
-            }).enumerate()
-            .fold([Vec::new(), Vec::new()], |mut vecs, (i, btn)|{
-                vecs[i%2].push(Element::new(btn));
+            })
+            .enumerate()
+            .fold([Vec::new(), Vec::new()], |mut vecs, (i, color_box)|{
+                vecs[i%2].push(color_box.into());
                 vecs
             });
         Column::new()
-            .push(Row::with_children(top))
-            .push(Row::with_children(bot))
+            .push(Row::with_children(top).spacing(1))
+            .push(Row::with_children(bot).spacing(1))
+            .spacing(1)
             .into()
     }
 
     fn update(&mut self, msg: Self::Message) {
         match msg {
-            Message::Updated(model) => {
-                self.buttons = create_buttons(model.as_ref().as_ref());
-                self.model = model;
-            },
-            _ => {}
+            Message::Grab(index, _) => self.dragged = Some(index),
+            Message::MoveColor(..) | Message::Release => self.dragged = None,
+            Message::Updated(model) => self.model = model,
+            Message::ActivateColor(_) => {},
         }
     }
-}
\ No newline at end of file
+}
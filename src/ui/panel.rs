@@ -9,6 +9,7 @@ pub mod left {
     use super::files::Message as FilesMessage;
     use super::files::FSMenu;
     use crate::io::default_dir;
+    use std::path::{Path, PathBuf};
 
     #[derive(Debug, Clone)]
     pub enum Message {
@@ -16,6 +17,9 @@ pub mod left {
         ShowResize,
         ShowOpen,
         ShowSave,
+        ShowImport,
+        ShowOpenCatalog,
+        ShowOpenPalette,
         Hide,
         Resize(Size),
         InputWidth(String),
@@ -39,16 +43,31 @@ pub mod left {
 
     pub struct Panel {
         size: Size,
+        /// directory the file dialogs are opened in
+        dir: PathBuf,
         state: State,
     }
 
     impl Default for Panel {
         fn default() -> Self {
+            Self::new(Size::default(), default_dir())
+        }
+    }
+
+    impl Panel {
+        pub fn new(size: Size, dir: PathBuf) -> Self {
             Self {
-                size: Size::default(),
+                size,
+                dir,
                 state: State::Empty,
             }
         }
+        pub fn dir(&self) -> &Path {
+            &self.dir
+        }
+        pub fn size(&self) -> Size {
+            self.size
+        }
     }
 
     impl AppWidget for Panel {
@@ -67,8 +86,11 @@ pub mod left {
             match msg {
                 Hide => { self.state = State::Empty },
                 ShowResize => { self.state = State::Resize(ResizeWidget::new(self.size))},
-                ShowOpen => { self.state = State::FS(Box::new(FSMenu::open(default_dir())))},
-                ShowSave => { self.state = State::FS(Box::new(FSMenu::save(default_dir())))},
+                ShowOpen => { self.state = State::FS(Box::new(FSMenu::open(self.dir.clone())))},
+                ShowSave => { self.state = State::FS(Box::new(FSMenu::save(self.dir.clone())))},
+                ShowImport => { self.state = State::FS(Box::new(FSMenu::import(self.dir.clone(), self.size)))},
+                ShowOpenCatalog => { self.state = State::FS(Box::new(FSMenu::open_catalog(self.dir.clone())))},
+                ShowOpenPalette => { self.state = State::FS(Box::new(FSMenu::open_palette(self.dir.clone())))},
                 Resize(size) => {
                     self.size = size;
                     if matches!(self.state, State::Resize(_)) {
@@ -81,7 +103,16 @@ pub mod left {
                         State::Resize(ref mut widget) => {widget.update(msg)},
                         State::FS(ref mut widget) => {
                             match msg {
-                                Message::FS(FilesMessage::Open(..)) | Message::FS(FilesMessage::Save(..)) => self.state = State::Empty,
+                                Message::FS(FilesMessage::Open(ref path, _)) |
+                                Message::FS(FilesMessage::OpenCatalog(ref path)) |
+                                Message::FS(FilesMessage::OpenPalette(ref path)) |
+                                Message::FS(FilesMessage::Save(ref path, ..)) |
+                                Message::FS(FilesMessage::Import(ref path, _)) => {
+                                    if let Some(dir) = path.parent() {
+                                        self.dir = dir.to_path_buf();
+                                    }
+                                    self.state = State::Empty
+                                },
                                 Message::FS(msg) => {widget.update(msg)},
                                 _ => {}
                             }
@@ -241,10 +272,12 @@ pub mod left {
 pub mod right {
     use super::*;
     use super::widget::{ColorBox, Gradient};
-    use std::collections::HashMap;
     use std::sync::Arc;
-    use super::style::Colored;
+    use super::style::{Colored, FSMenuItem, ToggledOn};
     use super::icon;
+    use crate::service::History;
+    use crate::io::Catalog;
+    use crate::model::reduce::{reduce_line, Reduction};
     use std::fmt::Debug;
 
     #[derive(Debug, Copy, Clone)]
@@ -258,12 +291,40 @@ pub mod right {
         Ignore,
         ShowBeads,
         ShowColors,
+        ShowHistory,
+        ShowCatalog,
         Hide,
         GridUpdated(Arc<dyn AsBeadsLine + Send + Sync>),
+        HistoryUpdated(Arc<History>),
+        Jump(isize),
+        InputHistoryDepth(String),
+        SetHistoryDepth(usize),
         ToggleCheckbox(usize),
+        InputSymbol(Color, String),
+        SetSymbol(Color, String),
         AddColor(Color),
         ConfigColor(ColorPart),
+        InputHex(String),
+        /// channel index, red is 0, and its text
+        InputChannel(usize, String),
+        TakeActiveColor,
         RemoveColor,
+        OpenCatalog,
+        /// replaces the palette with colors of a file, the pattern is kept
+        OpenPalette,
+        SaveDefaultPalette,
+        CatalogLoaded(Arc<Catalog>),
+        PickCatalogColor(Color, ColorInfo),
+        PreviewSnap,
+        CancelSnap,
+        /// pattern colors, catalog colors they are replaced with and the catalog info
+        SnapColors(Vec<(Color, Color, ColorInfo)>),
+        InputReduction(String),
+        ReduceByThreshold(bool),
+        MergeColors(Vec<(Color, Color)>),
+        /// replaces the first color with the second one everywhere
+        ReplaceColor(Color, Color),
+        SwapColors(Color, Color),
     }
 
     #[derive(Debug)]
@@ -271,10 +332,14 @@ pub mod right {
         None,
         Beads(BeadsWidget),
         Colors(ColorMenu),
+        History(HistoryWidget),
+        Catalog(CatalogWidget),
     }
 
     pub struct RightPanel {
         line_ref: Arc<dyn AsBeadsLine>,
+        history: Arc<History>,
+        catalog: Arc<Catalog>,
         scroll: scrollable::State,
         state: State,
     }
@@ -283,6 +348,8 @@ pub mod right {
         pub fn new(line_ref: Arc<dyn AsBeadsLine>) -> Self {
             Self {
                 line_ref,
+                history: Default::default(),
+                catalog: Arc::new(Catalog::example()),
                 scroll: Default::default(),
                 state: State::None,
             }
@@ -297,7 +364,9 @@ pub mod right {
                 match self.state {
                     State::None => { Space::new(Length::Units(0), Length::Units(0)).into() }
                     State::Beads(ref mut widget) => { widget.view() }
-                    State::Colors(ref mut widget) => widget.view()
+                    State::Colors(ref mut widget) => widget.view(),
+                    State::History(ref mut widget) => widget.view(),
+                    State::Catalog(ref mut widget) => widget.view(),
                 })
                 .into()
         }
@@ -306,54 +375,103 @@ pub mod right {
             match &mut self.state {
                 State::Beads(ref mut widget) => widget.update(msg.clone()),
                 State::Colors(ref mut widget) => widget.update(msg.clone()),
+                State::History(ref mut widget) => widget.update(msg.clone()),
+                State::Catalog(ref mut widget) => widget.update(msg.clone()),
                 State::None => {},
             }
             match msg {
                 Message::Hide => self.state = State::None,
-                Message::ShowColors => self.state = State::Colors(Default::default()),
+                Message::ShowColors => self.state = State::Colors(ColorMenu::new(self.line_ref.clone())),
                 Message::ShowBeads => {
-                    self.state = State::Beads(
-                        BeadsWidget {
-                            line_ref: self.line_ref.clone(),
-                        }
-                    );
+                    self.state = State::Beads(BeadsWidget::new(self.line_ref.clone()));
                 }
+                Message::ShowHistory => self.state = State::History(HistoryWidget::new(self.history.clone())),
+                Message::ShowCatalog => {
+                    self.state = State::Catalog(CatalogWidget::new(self.catalog.clone(), self.line_ref.clone()));
+                },
+                Message::CatalogLoaded(catalog) => {
+                    self.catalog = catalog.clone();
+                    self.state = State::Catalog(CatalogWidget::new(catalog, self.line_ref.clone()));
+                },
                 Message::GridUpdated(grid) => self.line_ref = grid,
+                Message::HistoryUpdated(history) => self.history = history,
                 _ => {}
             }
         }
     }
 
-    pub trait AsBeadsLine :  Debug + AsRef<BeadsLine<Bead<Color>>> {}
-    impl <T: AsRef< BeadsLine<Bead<Color>> > + Debug> AsBeadsLine for T {}
+    pub trait AsBeadsLine : Debug + AsRef<BeadsLine<Bead<Color>>> + AsRef<Symbols<Color>> + AsRef<Palette<Color>> {
+        fn beads_line(&self) -> &BeadsLine<Bead<Color>> {
+            self.as_ref()
+        }
+        fn symbols(&self) -> &Symbols<Color> {
+            self.as_ref()
+        }
+        fn palette(&self) -> &Palette<Color> {
+            self.as_ref()
+        }
+    }
+    impl <T> AsBeadsLine for T
+        where T: AsRef<BeadsLine<Bead<Color>>> + AsRef<Symbols<Color>> + AsRef<Palette<Color>> + Debug {}
 
     #[derive(Debug)]
     struct BeadsWidget {
-        line_ref: Arc<dyn AsBeadsLine>
+        line_ref: Arc<dyn AsBeadsLine>,
+        /// color, its symbol field and the edited symbol
+        symbols: Vec<(Color, text_input::State, String)>,
+        /// replace and swap buttons of the summary rows
+        buttons: Vec<(button::State, button::State)>,
+    }
+
+    impl BeadsWidget {
+        fn new(line_ref: Arc<dyn AsBeadsLine>) -> Self {
+            let mut widget = Self {
+                line_ref: line_ref.clone(),
+                symbols: Vec::new(),
+                buttons: Vec::new(),
+            };
+            widget.set_line(line_ref);
+            widget
+        }
+
+        fn set_line(&mut self, line_ref: Arc<dyn AsBeadsLine>) {
+            self.symbols = line_ref.symbols().legend(line_ref.beads_line()).into_iter()
+                .map(|(color, _, symbol)|(color, Default::default(), symbol.to_string()))
+                .collect();
+            self.buttons.resize_with(self.symbols.len(), Default::default);
+            self.line_ref = line_ref;
+        }
     }
-    const SYMBOLS: [&str;26] = ["A","B","C","D","E","F","G","H","I","J","K","L","M",
-                                "N","O","P","Q","R","S","T","U","V","W","X","Y","Z"];
 
     impl AppWidget for BeadsWidget {
         type Message = Message;
 
         fn view(&mut self) -> Element<'_, Self::Message> {
-            let line = self.line_ref.as_ref().as_ref();
-            let summary = line.map(|x|x.color.clone()).summary();
-            let mut sorted_summary: Vec<_> = summary.iter().collect();
-            let undefined = "?";
-            sorted_summary.sort_unstable_by_key(|(&color, _)| { color.to_string() });
-            let mut range = SYMBOLS.iter();
-            let symbols: HashMap<_,_> = sorted_summary.iter().map(|(obj, _)|{
-                (obj.clone(), *range.next().unwrap_or(&undefined))
-            }).collect();
-            let summary = Column::with_children(sorted_summary.iter().map(|(&color, &count)| {
-                Row::new().spacing(5)
-                    .push(Text::new(symbols.get(&color).unwrap_or(&undefined).to_string()).width(Length::Units(15)))
+            let line = self.line_ref.beads_line();
+            let symbols = self.line_ref.symbols();
+            let palette = self.line_ref.palette();
+            let activated = *palette.activated();
+            let counts = line.map(|x|x.color).summary();
+            let rows = self.symbols.iter_mut().zip(self.buttons.iter_mut());
+            let summary = Column::with_children(rows.map(|((color, state, symbol), (replace, swap))| {
+                let color = *color;
+                let mut btn_replace = Button::new(replace, Text::new("Replace"));
+                let mut btn_swap = Button::new(swap, Text::new("Swap"));
+                if color != activated {
+                    btn_replace = btn_replace.on_press(Message::ReplaceColor(color, activated));
+                    btn_swap = btn_swap.on_press(Message::SwapColors(color, activated));
+                }
+                Row::new().spacing(5).align_items(Align::Center)
+                    .push(TextInput::new(state, "", symbol, move |s|Message::InputSymbol(color, s))
+                        .on_submit(Message::SetSymbol(color, symbol.clone()))
+                        .width(Length::Units(40)))
                     .push(ColorBox::new(color))
-                    .push(Text::new(count.to_string()))
+                    .push(Text::new(counts.get(&color).copied().unwrap_or_default().to_string()))
+                    .push(btn_replace)
+                    .push(btn_swap)
+                    .push(Text::new(palette.info(&color).map(ColorInfo::label).unwrap_or_default()))
                     .into()
-            }).collect()).into();
+            }).collect()).spacing(1).into();
 
             let schema = line.line().iter()
                 .enumerate()
@@ -361,9 +479,9 @@ pub mod right {
                     Row::new().spacing(5).align_items(Align::Center)
                         .push(Checkbox::new(
                             *filled,
-                            symbols.get(color).unwrap_or(&undefined).to_string(),
+                            symbols.get(color),
                             move |_| Message::ToggleCheckbox(i)
-                        ).spacing(1).width(Length::Units(35)))
+                        ).spacing(1).width(Length::Units(45)))
                         .push(ColorBox::new(color.clone()))
                         .push(Text::new(count.to_string()))
                         .into()
@@ -373,7 +491,7 @@ pub mod right {
             let schema = Column::with_children(schema).spacing(1).into();
             Column::with_children(vec![
                 Text::new(format!("Width: {}", line.width)).into(),
-                Text::new("Summary").into(),
+                Text::new("Summary, replace or swap with the active color").into(),
                 summary,
                 Text::new("Schema").into(),
                 schema
@@ -382,7 +500,12 @@ pub mod right {
 
         fn update(&mut self, msg: Self::Message) {
             match msg {
-                Message::GridUpdated(model) => self.line_ref = model,
+                Message::GridUpdated(model) => self.set_line(model),
+                Message::InputSymbol(color, text) => {
+                    if let Some((_, _, symbol)) = self.symbols.iter_mut().find(|(item, _, _)|*item == color) {
+                        *symbol = text;
+                    }
+                },
                 _ => {},
             }
         }
@@ -390,20 +513,143 @@ pub mod right {
 
     #[derive(Debug)]
     struct ColorMenu {
+        line_ref: Arc<dyn AsBeadsLine>,
         btn_add: button::State,
+        btn_edit: button::State,
+        btn_take: button::State,
         btn_remove: button::State,
+        btn_load: button::State,
+        btn_default: button::State,
+        /// the edited color, exact when it is entered as numbers
+        color: Color,
         hsl: colors::Hsl,
         sliders: (slider::State, slider::State, slider::State),
+        input_hex: text_input::State,
+        hex: String,
+        input_channels: [text_input::State; 3],
+        channels: [String; 3],
+        error: Option<String>,
+        reduction: ReduceWidget,
     }
 
-    impl Default for ColorMenu {
-        fn default() -> Self {
-            Self {
+    impl ColorMenu {
+        fn new(line_ref: Arc<dyn AsBeadsLine>) -> Self {
+            let mut menu = Self {
+                line_ref: line_ref.clone(),
                 btn_add: Default::default(),
+                btn_edit: Default::default(),
+                btn_take: Default::default(),
                 btn_remove: Default::default(),
+                btn_load: Default::default(),
+                btn_default: Default::default(),
+                color: Default::default(),
                 hsl: colors::Hsl::default(),
                 sliders: Default::default(),
+                input_hex: Default::default(),
+                hex: String::new(),
+                input_channels: Default::default(),
+                channels: Default::default(),
+                error: None,
+                reduction: ReduceWidget::new(line_ref.clone()),
+            };
+            menu.set_color(*line_ref.palette().activated());
+            menu
+        }
+
+        /// Sets the color with all its fields
+        fn set_color(&mut self, color: Color) {
+            self.set_numbers(color);
+            self.hex = color.to_string();
+            self.hsl = color_2_hsl(color);
+        }
+
+        /// Sets the color and the fields of numbers only, so the edited field keeps its text
+        fn set_numbers(&mut self, color: Color) {
+            let Color {r, g, b} = color;
+            self.channels = [r.to_string(), g.to_string(), b.to_string()];
+            self.color = color;
+            self.error = None;
+        }
+    }
+
+    /// Merges similar colors, by the count of colors left or by the color difference
+    #[derive(Debug)]
+    struct ReduceWidget {
+        line_ref: Arc<dyn AsBeadsLine>,
+        input: text_input::State,
+        value: String,
+        by_threshold: bool,
+        btn_merge: button::State,
+        /// merged colors and count of the changed beads
+        result: Option<(Vec<(Color, Color)>, usize)>,
+    }
+
+    impl ReduceWidget {
+        fn new(line_ref: Arc<dyn AsBeadsLine>) -> Self {
+            let mut widget = Self {
+                line_ref,
+                input: Default::default(),
+                value: "10".to_string(),
+                by_threshold: false,
+                btn_merge: Default::default(),
+                result: None,
+            };
+            widget.calculate();
+            widget
+        }
+
+        fn calculate(&mut self) {
+            let reduction = if self.by_threshold {
+                self.value.parse().ok().filter(|&threshold: &f64|threshold >= 0.0).map(Reduction::Threshold)
+            } else {
+                self.value.parse().ok().filter(|&count|count > 0).map(Reduction::Count)
+            };
+            self.result = reduction.map(|reduction|{
+                let (mapping, changed) = reduce_line(self.line_ref.beads_line(), reduction);
+                (mapping.into_iter().collect(), changed)
+            });
+        }
+    }
+
+    impl AppWidget for ReduceWidget {
+        type Message = Message;
+
+        fn view(&mut self) -> Element<'_, Self::Message> {
+            let colors = self.line_ref.beads_line().map(|bead|bead.color).summary().len();
+            let mut btn_merge = Button::new(&mut self.btn_merge, Text::new("Merge"));
+            let report = match self.result {
+                Some((ref mapping, changed)) => {
+                    if !mapping.is_empty() {
+                        btn_merge = btn_merge.on_press(Message::MergeColors(mapping.clone()));
+                    }
+                    format!("Colors: {} → {}, beads changed: {}", colors, colors - mapping.len(), changed)
+                },
+                None => format!("Colors: {}", colors),
+            };
+            Column::new().spacing(5)
+                .push(Text::new("Reduce colors"))
+                .push(Checkbox::new(self.by_threshold, "Merge by color difference", Message::ReduceByThreshold))
+                .push(Row::new().spacing(5).align_items(Align::Center)
+                    .push(Text::new(if self.by_threshold { "Difference: " } else { "Colors: " }))
+                    .push(TextInput::new(&mut self.input, "10", &self.value, Message::InputReduction)
+                        .width(Length::Units(50)))
+                    .push(btn_merge)
+                )
+                .push(Text::new(report))
+                .into()
+        }
+
+        fn update(&mut self, msg: Self::Message) {
+            match msg {
+                Message::GridUpdated(line_ref) => self.line_ref = line_ref,
+                Message::InputReduction(value) => self.value = value,
+                Message::ReduceByThreshold(by_threshold) => {
+                    self.by_threshold = by_threshold;
+                    self.value = if by_threshold { "5" } else { "10" }.to_string();
+                },
+                _ => return,
             }
+            self.calculate();
         }
     }
 
@@ -411,6 +657,10 @@ pub mod right {
         let (r,g,b) = colors::Srgb::from(hsl).into_components();
         iced::Color::from_rgb(r,g,b)
     }
+    fn color_2_hsl(Color {r, g, b}: Color) -> colors::Hsl {
+        let max = u8::MAX as f32;
+        colors::Hsl::from(colors::Srgb::new(r as f32 / max, g as f32 / max, b as f32 / max))
+    }
     impl AppWidget for ColorMenu {
         type Message = Message;
 
@@ -448,14 +698,53 @@ pub mod right {
                     light,
                     |light|Message::ConfigColor(ColorPart::Lightness(light))
                 ));
-            let color = hsl_2_color(self.hsl);
-            let submit = Container::new(
-                Button::new(&mut self.btn_add, icon::ADD.svg()).width(Length::Units(30))
-                    .on_press(Message::AddColor(color.into()))
-            ).width(Length::Units(100))
+            let channels = self.input_channels.iter_mut()
+                .zip(self.channels.iter())
+                .zip(["R", "G", "B"].iter())
+                .enumerate()
+                .fold(Row::new().spacing(5).align_items(Align::Center), |row, (i, ((state, value), name))| {
+                    row.push(Text::new(*name))
+                        .push(TextInput::new(state, "0", value, move |s|Message::InputChannel(i, s))
+                            .width(Length::Units(40)))
+                });
+            let numbers = Column::new().spacing(5)
+                .push(Row::new().spacing(5).align_items(Align::Center)
+                    .push(Text::new("Hex"))
+                    .push(TextInput::new(&mut self.input_hex, "#FFFFFF", &self.hex, Message::InputHex)
+                        .width(Length::Units(80)))
+                )
+                .push(channels)
+                .push(Text::new(self.error.as_deref().unwrap_or(""))
+                    .color(iced::Color::from_rgb(0.8, 0.0, 0.0)));
+
+            let reduction = self.reduction.view();
+            let activated = *self.line_ref.palette().activated();
+            let color: iced::Color = self.color.into();
+            let mut btn_edit = Button::new(&mut self.btn_edit, Text::new("Edit active"));
+            if self.color != activated {
+                btn_edit = btn_edit.on_press(Message::ReplaceColor(activated, self.color));
+            }
+            let submit = Container::new(Row::new().spacing(5)
+                .push(Button::new(&mut self.btn_add, icon::ADD.svg()).width(Length::Units(30))
+                    .on_press(Message::AddColor(self.color)))
+                .push(btn_edit)
+                .push(Button::new(&mut self.btn_take, Text::new("Take active")).on_press(Message::TakeActiveColor))
+            ).width(Length::Units(250))
                 .style(Colored(color));
 
-            Element::new(column.push(submit)).explain(iced::Color::BLACK)
+            Element::new(column
+                .push(Space::new(Length::Fill, Length::Units(5)))
+                .push(numbers)
+                .push(submit)
+                .push(Row::new().spacing(5)
+                    .push(Button::new(&mut self.btn_load, Text::new("Load palette")).on_press(Message::OpenPalette))
+                    .push(Button::new(&mut self.btn_default, Text::new("Save as default"))
+                        .on_press(Message::SaveDefaultPalette))
+                )
+                .push(Space::new(Length::Fill, Length::Units(10)))
+                .push(reduction)
+            )
+                .explain(iced::Color::BLACK)
 
         }
 
@@ -467,9 +756,232 @@ pub mod right {
                         ColorPart::Saturation(sat) => self.hsl.saturation = sat,
                         ColorPart::Lightness(lightness) => self.hsl.lightness = lightness,
                     }
+                    let color = hsl_2_color(self.hsl).into();
+                    self.set_numbers(color);
+                    self.hex = color.to_string();
+                },
+                Message::InputHex(hex) => {
+                    match hex.parse::<Color>() {
+                        Ok(color) => {
+                            self.set_numbers(color);
+                            self.hsl = color_2_hsl(color);
+                        },
+                        Err(e) => self.error = Some(e.to_string()),
+                    }
+                    self.hex = hex;
+                },
+                Message::InputChannel(index, value) => {
+                    let mut channels = self.channels.clone();
+                    channels[index] = value;
+                    let parsed = channels.iter()
+                        .map(|channel|channel.trim().parse::<u8>())
+                        .collect::<Result<Vec<_>, _>>();
+                    match parsed.as_deref() {
+                        Ok(&[r, g, b]) => {
+                            let color = Color {r, g, b};
+                            self.color = color;
+                            self.hex = color.to_string();
+                            self.hsl = color_2_hsl(color);
+                            self.error = None;
+                        },
+                        _ => self.error = Some("Color components are numbers from 0 to 255".to_string()),
+                    }
+                    self.channels = channels;
+                },
+                Message::TakeActiveColor => self.set_color(*self.line_ref.palette().activated()),
+                Message::GridUpdated(line_ref) => {
+                    self.line_ref = line_ref.clone();
+                    self.reduction.update(Message::GridUpdated(line_ref));
                 },
+                msg => self.reduction.update(msg),
+            }
+        }
+    }
+
+    #[derive(Debug)]
+    struct CatalogWidget {
+        catalog: Arc<Catalog>,
+        line_ref: Arc<dyn AsBeadsLine>,
+        btn_open: button::State,
+        btn_snap: button::State,
+        btn_apply: button::State,
+        btn_cancel: button::State,
+        buttons: Vec<button::State>,
+        /// pattern colors with the nearest catalog colors, shown before snapping
+        preview: Option<Vec<(Color, Color, ColorInfo, f64)>>,
+    }
+
+    impl CatalogWidget {
+        fn new(catalog: Arc<Catalog>, line_ref: Arc<dyn AsBeadsLine>) -> Self {
+            Self {
+                buttons: vec![Default::default(); catalog.colors.len()],
+                btn_open: Default::default(),
+                btn_snap: Default::default(),
+                btn_apply: Default::default(),
+                btn_cancel: Default::default(),
+                preview: None,
+                catalog,
+                line_ref,
+            }
+        }
+
+        /// Palette colors followed by the pattern colors missing in the palette
+        fn pattern_colors(&self) -> Vec<Color> {
+            let mut colors = self.line_ref.palette().colors().to_vec();
+            for (Bead {color, ..}, _) in self.line_ref.beads_line().line() {
+                if !colors.contains(color) {
+                    colors.push(*color);
+                }
+            }
+            colors
+        }
+
+        fn preview_view<'a>(
+            preview: &'a [(Color, Color, ColorInfo, f64)],
+            btn_apply: &'a mut button::State,
+            btn_cancel: &'a mut button::State
+        ) -> Element<'a, Message> {
+            let mapping = preview.iter().map(|(from, to, info, _)|(*from, *to, info.clone())).collect();
+            let rows = preview.iter()
+                .map(|(from, to, info, difference)|{
+                    Row::new().spacing(5).align_items(Align::Center)
+                        .push(ColorBox::new(*from))
+                        .push(Text::new("→"))
+                        .push(ColorBox::new(*to))
+                        .push(Text::new(format!("ΔE {:.1}", difference)).width(Length::Units(60)))
+                        .push(Text::new(info.label()))
+                        .into()
+                })
+                .collect();
+            Column::new().spacing(5)
+                .push(Text::new("Snap to catalog"))
+                .push(Column::with_children(rows).spacing(1))
+                .push(Row::new().spacing(5)
+                    .push(Button::new(btn_apply, Text::new("Apply")).on_press(Message::SnapColors(mapping)))
+                    .push(Button::new(btn_cancel, Text::new("Cancel")).on_press(Message::CancelSnap))
+                )
+                .into()
+        }
+    }
+
+    impl AppWidget for CatalogWidget {
+        type Message = Message;
+
+        fn view(&mut self) -> Element<'_, Self::Message> {
+            let colors = self.buttons.iter_mut().zip(self.catalog.colors.iter())
+                .map(|(state, (color, info))| {
+                    Button::new(state, Row::new().spacing(5).align_items(Align::Center)
+                        .push(ColorBox::new(*color))
+                        .push(Text::new(info.label()))
+                    )
+                        .on_press(Message::PickCatalogColor(*color, info.clone()))
+                        .style(FSMenuItem)
+                        .width(Length::Fill)
+                        .into()
+                })
+                .collect();
+            let mut column = Column::new().width(Length::Units(250)).spacing(5)
+                .push(Row::new().spacing(5).align_items(Align::Center)
+                    .push(Text::new(&self.catalog.name))
+                    .push(Button::new(&mut self.btn_open, Text::new("Open catalog"))
+                        .on_press(Message::OpenCatalog))
+                );
+            column = match self.preview {
+                Some(ref preview) => column.push(Self::preview_view(preview, &mut self.btn_apply, &mut self.btn_cancel)),
+                None => column.push(Button::new(&mut self.btn_snap, Text::new("Snap pattern to catalog"))
+                    .on_press(Message::PreviewSnap)),
+            };
+            column.push(Column::with_children(colors).spacing(1)).into()
+        }
+
+        fn update(&mut self, msg: Self::Message) {
+            match msg {
+                Message::GridUpdated(line_ref) => self.line_ref = line_ref,
+                Message::PreviewSnap => self.preview = Some(self.catalog.snap(&self.pattern_colors())),
+                Message::CancelSnap | Message::SnapColors(_) => self.preview = None,
+                _ => {},
+            }
+        }
+    }
+
+    #[derive(Debug)]
+    struct HistoryWidget {
+        history: Arc<History>,
+        buttons: Vec<button::State>,
+        input_depth: text_input::State,
+        depth: String,
+        btn_depth: button::State,
+    }
+
+    impl HistoryWidget {
+        fn new(history: Arc<History>) -> Self {
+            let mut widget = Self {
+                history: Default::default(),
+                buttons: Vec::new(),
+                input_depth: Default::default(),
+                depth: String::new(),
+                btn_depth: Default::default(),
+            };
+            widget.set_history(history);
+            widget
+        }
+
+        fn set_history(&mut self, history: Arc<History>) {
+            self.buttons.resize_with(history.undo.len() + history.redo.len() + 1, Default::default);
+            self.depth = history.depth.to_string();
+            self.history = history;
+        }
+    }
+
+    impl AppWidget for HistoryWidget {
+        type Message = Message;
+
+        fn view(&mut self) -> Element<'_, Self::Message> {
+            let history = &self.history;
+            let current = history.undo.len();
+            // oldest state first, the redo stack holds the nearest state last
+            let labels = std::iter::once("Initial state")
+                .chain(history.undo.iter().map(String::as_str))
+                .chain(history.redo.iter().rev().map(String::as_str));
+            let entries = self.buttons.iter_mut().zip(labels).enumerate()
+                .map(|(i, (state, label))| {
+                    let btn = Button::new(state, Text::new(label)).width(Length::Fill);
+                    if i == current {
+                        btn.style(ToggledOn).into()
+                    } else {
+                        btn.on_press(Message::Jump(i as isize - current as isize)).style(FSMenuItem).into()
+                    }
+                })
+                .collect();
+
+            let depth_field = TextInput::new(
+                &mut self.input_depth,
+                "100",
+                &self.depth,
+                Message::InputHistoryDepth,
+            ).width(Length::Units(50));
+            let mut btn_ok = Button::new(&mut self.btn_depth, Text::new("OK"));
+            if let Ok(depth) = self.depth.parse::<usize>() {
+                if depth > 0 {
+                    btn_ok = btn_ok.on_press(Message::SetHistoryDepth(depth));
+                }
+            }
+            Column::new().width(Length::Units(200))
+                .push(Row::new().spacing(5).align_items(Align::Center)
+                    .push(Text::new("Depth: "))
+                    .push(depth_field)
+                    .push(btn_ok)
+                )
+                .push(Column::with_children(entries))
+                .into()
+        }
+
+        fn update(&mut self, msg: Self::Message) {
+            match msg {
+                Message::HistoryUpdated(history) => self.set_history(history),
+                Message::InputHistoryDepth(s) => self.depth = s,
                 _ => {}
             }
         }
     }
-}
\ No newline at end of file
+}
@@ -3,25 +3,6 @@ use iced::container;
 use button::Style;
 use iced::{Color, Background};
 
-pub struct ColorButton(pub Color);
-
-impl button::StyleSheet for ColorButton {
-    fn active(&self) -> Style {
-        Style {
-            background: Some(Background::Color(self.0.clone())),
-            ..Default::default()
-        }
-    }
-
-    fn disabled(&self) -> Style {
-        Style {
-            border_width: 2.0,
-            border_color: Color::BLACK,
-            ..self.active()
-        }
-    }
-}
-
 pub struct FSMenuItem;
 
 pub struct ToggledOn;
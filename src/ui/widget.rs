@@ -13,6 +13,7 @@ pub struct ColorBox<T> {
     height: Length,
     press_message: Option<T>,
     over_message: Option<T>,
+    release_message: Option<T>,
     border_color: Color,
 }
 
@@ -24,6 +25,7 @@ impl <T> ColorBox<T> {
             height: Length::Units(30),
             press_message: None,
             over_message: None,
+            release_message: None,
             border_color: Color::BLACK,
         }
     }
@@ -47,6 +49,10 @@ impl <T> ColorBox<T> {
         self.over_message = Some(msg);
         self
     }
+    pub fn on_release(mut self, msg: T) -> Self {
+        self.release_message = Some(msg);
+        self
+    }
 }
 
 impl<Message:Clone> Widget<Message, Renderer> for ColorBox<Message> {
@@ -110,7 +116,12 @@ impl<Message:Clone> Widget<Message, Renderer> for ColorBox<Message> {
                 _ => {}
             }
         };
-        if let (Some(msg), Event::Mouse(mouse::Event::CursorMoved {..})) = (&self.over_message, event) {
+        if let (Some(msg), Event::Mouse(mouse::Event::CursorMoved {..})) = (&self.over_message, event.clone()) {
+            if layout.bounds().contains(cursor_position) {
+                messages.push(msg.clone())
+            }
+        };
+        if let (Some(msg), Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left))) = (&self.release_message, event) {
             if layout.bounds().contains(cursor_position) {
                 messages.push(msg.clone())
             }
@@ -126,10 +137,10 @@ impl<'a, M: 'a + Clone> Into<Element<'a,M>> for ColorBox<M> {
     }
 }
 
-pub struct MouseListener<M>(pub M);
-
+/// Turns the events reaching the window into messages, wherever the widget is placed
+pub struct EventListener<M>(pub fn(&Event) -> Option<M>);
 
-impl<Message: Clone> Widget<Message, Renderer> for MouseListener<Message> {
+impl<Message> Widget<Message, Renderer> for EventListener<Message> {
     fn width(&self) -> Length {
         Length::Units(0)
     }
@@ -164,12 +175,11 @@ impl<Message: Clone> Widget<Message, Renderer> for MouseListener<Message> {
                 messages: &mut Vec<Message>,
                 _renderer: &Renderer,
                 _clipboard: Option<&dyn Clipboard>) -> event::Status {
-        if matches!(event, Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left))) {
-            messages.push(self.0.clone())
-        }
+        messages.extend((self.0)(&event));
         event::Status::Ignored
     }
 }
+
 #[derive(Debug, Copy, Clone)]
 pub enum Gradient {
     Hue,